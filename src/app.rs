@@ -1,33 +1,177 @@
 pub use area::Area;
+pub use bookmarks::{Bookmark, Bookmarks};
+pub use browser::Browser;
 pub use cell::Cell;
-use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+pub use command::{Command, CommandLine};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+pub use edit::{Editor, Paste};
+pub use error::{Error, ParseError};
+pub use heat::HeatMap;
+pub use history::History;
+pub use keys::{Action, Keymap, Mode};
+pub use orientation::Orientation;
 use ratatui::{backend::Backend, Terminal};
-use std::{io, str::FromStr, time::Duration};
-pub use universe::Universe;
+use record::{Recorder, Recording};
+pub use render::Renderer;
+pub use rule::Rule;
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::{Duration, Instant},
+};
+pub use theme::Theme;
+pub use universe::{Topology, Universe};
 
-/// Default poll duration
-const DEF_DUR: Duration = Duration::from_millis(400);
+/// Default speed: generations per second
+pub const DEF_SPEED: f64 = 2.5;
+/// Slowest speed allowed: generations per second
+const MIN_SPEED: f64 = 0.1;
+/// Fastest speed allowed: generations per second
+const MAX_SPEED: f64 = 100_000.;
+/// Maximum number of frames rendered per second
+const MAX_FPS: u64 = 60;
+/// Time available for one frame
+const FRAME: Duration = Duration::from_nanos(1_000_000_000 / MAX_FPS);
+/// Number of cells scrolled at once
+const SCROLL: i32 = 8;
+/// Memory available for undoing edits: 16 MiB
+const UNDO_BUDGET: usize = 16 << 20;
+/// Number of generations the heat map covers
+const HEAT_GENERATIONS: usize = 100;
 /// Pause duration: a day
 const PAUSE: Duration = Duration::from_secs(60 * 60 * 24);
 
 mod area;
+/// Snapshots of the universe to come back to
+mod bookmarks;
+/// Pattern browser side panel
+pub mod browser;
 mod cell;
+/// System clipboard via the OSC 52 escape sequence
+mod clipboard;
+/// Ex-style command line
+pub mod command;
+/// Settings from the config file
+pub mod config;
+/// Editing the board: selection and clipboard
+pub mod edit;
+mod error;
+/// Pattern file formats
+pub mod format;
+/// How often cells changed
+mod heat;
+/// Undoing and redoing edits
+mod history;
+/// Which key does what
+pub mod keys;
+/// Finding pattern files
+pub mod load;
+/// Rotating and mirroring patterns
+mod orientation;
+/// Recording generations as animated GIFs
+pub mod record;
+/// Drawing cells with braille dots, blocks or ASCII
+mod render;
+/// Life-like rules
+mod rule;
 /// Starting shapes
 pub mod shapes;
+/// Colours
+mod theme;
 /// ui
 mod ui;
 /// Conway's Game of Life universe
 mod universe;
 
 #[cfg(test)]
-mod tests;
+pub(crate) mod tests;
+
+/// The patterns and settings the app was started with, from the config file and the command line
+#[derive(Debug, Clone)]
+struct Startup {
+    universes: Vec<Universe>,
+    i: usize,
+    speed: f64,
+    paused: bool,
+    rule: Option<Rule>,
+    topology: Topology,
+    size: Option<Area>,
+    renderer: Renderer,
+    theme: Theme,
+    aging: bool,
+}
+
+/// Keeps track of how many generations are due at a given speed
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Pacer {
+    /// generations owed, but not yet run
+    owed: f64,
+}
+impl Pacer {
+    /// Number of generations due after `elapsed` time at `speed` generations per second
+    pub fn due(&mut self, elapsed: Duration, speed: f64) -> u64 {
+        self.owed += elapsed.as_secs_f64() * speed;
+        let due = self.owed.floor();
+        self.owed -= due;
+        due as u64
+    }
+    /// Forget about owed generations
+    pub fn reset(&mut self) {
+        self.owed = 0.;
+    }
+}
 
 pub struct App {
     pub available_universes: Vec<Universe>,
     universe: Universe,
     i: usize,
-    pub poll_t: Duration,
+    /// generations per second
+    pub speed: f64,
+    paused: bool,
+    pacer: Pacer,
     pub area: Area,
+    /// pattern browser, if open
+    pub browser: Option<Browser>,
+    /// message shown to the user until the next key press, eg. an error
+    pub message: Option<String>,
+    /// top left corner of the visible part of the universe: (row, col)
+    pub offset: (u16, u16),
+    /// overrides the rule of the patterns
+    pub rule: Option<Rule>,
+    pub topology: Topology,
+    /// fixed size of the universe instead of the size of the terminal
+    pub size: Option<Area>,
+    /// applied to patterns on (re)start
+    pub orientation: Orientation,
+    /// edit mode, if on
+    pub editor: Option<Editor>,
+    /// copied or cut part of the board
+    pub clipboard: Option<Universe>,
+    /// escape sequence written to the terminal before the next frame
+    pub output: Option<String>,
+    /// edits of the board, to undo
+    pub history: History,
+    pub renderer: Renderer,
+    pub theme: Theme,
+    /// colouring cells by age
+    pub aging: bool,
+    /// heat map overlay, if on
+    pub heat: Option<HeatMap>,
+    pub keymap: Keymap,
+    /// showing the keys of the current mode
+    pub help: bool,
+    /// generations run since the (re)start
+    pub generation: u64,
+    /// generation being jumped to, running as fast as possible
+    pub target: Option<u64>,
+    pub bookmarks: Bookmarks,
+    /// GIF being recorded and where
+    pub recording: Option<Recording<BufWriter<File>>>,
+    pub command_line: CommandLine,
+    /// what a reset goes back to, remembered once running
+    startup: Option<Startup>,
 }
 impl Default for App {
     fn default() -> Self {
@@ -35,8 +179,33 @@ impl Default for App {
             area: Area::default(),
             universe: Universe::default(),
             i: 0,
-            poll_t: DEF_DUR,
+            speed: DEF_SPEED,
+            paused: false,
+            pacer: Pacer::default(),
             available_universes: shapes::all(),
+            browser: None,
+            message: None,
+            offset: (0, 0),
+            rule: None,
+            topology: Topology::default(),
+            size: None,
+            orientation: Orientation::default(),
+            editor: None,
+            clipboard: None,
+            output: None,
+            history: History::new(UNDO_BUDGET),
+            renderer: Renderer::default(),
+            theme: Theme::default(),
+            aging: false,
+            heat: None,
+            keymap: Keymap::default(),
+            help: false,
+            generation: 0,
+            target: None,
+            bookmarks: Bookmarks::default(),
+            recording: None,
+            command_line: CommandLine::default(),
+            startup: None,
         }
     }
 }
@@ -47,17 +216,116 @@ impl App {
             ..self
         }
     }
-    pub fn new(area: Area, available_universes: Vec<Universe>, poll_t: Duration) -> Self {
+    /// Jump to `generation` once started
+    pub fn with_generation(self, generation: Option<u64>) -> Self {
+        Self {
+            target: generation,
+            ..self
+        }
+    }
+    pub fn with_keymap(self, keymap: Keymap) -> Self {
+        Self { keymap, ..self }
+    }
+    pub fn with_speed(self, speed: f64) -> Self {
+        Self {
+            speed: speed.clamp(MIN_SPEED, MAX_SPEED),
+            ..self
+        }
+    }
+    pub fn with_paused(self, paused: bool) -> Self {
+        Self { paused, ..self }
+    }
+    pub fn with_rule(self, rule: Option<Rule>) -> Self {
+        Self { rule, ..self }
+    }
+    pub fn with_topology(self, topology: Topology) -> Self {
+        Self { topology, ..self }
+    }
+    pub fn with_size(self, size: Option<Area>) -> Self {
+        Self { size, ..self }
+    }
+    pub fn with_renderer(self, renderer: Renderer) -> Self {
+        Self { renderer, ..self }
+    }
+    pub fn with_theme(self, theme: Theme) -> Self {
+        Self { theme, ..self }
+    }
+    pub fn with_aging(self, aging: bool) -> Self {
+        Self { aging, ..self }
+    }
+    /// Start with the `i`th pattern
+    ///
+    /// # Errors
+    ///
+    /// `i` is out of range
+    pub fn with_index(self, i: usize) -> Result<Self, Error> {
+        if i >= self.len() {
+            return Err(Error::Invalid {
+                what: "pattern index",
+                value: format!("{i}, there are {} patterns", self.len()),
+            });
+        }
+        Ok(Self { i, ..self })
+    }
+    /// Start with the pattern called `name`, ignoring case
+    ///
+    /// # Errors
+    ///
+    /// there's no such pattern
+    pub fn with_pattern(self, name: &str) -> Result<Self, Error> {
+        let true_len = self.available_universes.len();
+        let matches = |univ: &Universe| univ.name().eq_ignore_ascii_case(name);
+        let i = self
+            .available_universes
+            .iter()
+            .position(matches)
+            .or_else(|| {
+                (0..shapes::N)
+                    .position(|i| matches(&shapes::get_special(i, Area::default())))
+                    .map(|i| true_len + i)
+            })
+            .ok_or_else(|| Error::Invalid {
+                what: "pattern",
+                value: name.to_string(),
+            })?;
+        Ok(Self { i, ..self })
+    }
+    pub fn new(area: Area, available_universes: Vec<Universe>, speed: f64) -> Self {
         App {
             area,
             universe: available_universes[0].clone(),
             i: 0,
-            poll_t,
+            speed: speed.clamp(MIN_SPEED, MAX_SPEED),
+            paused: false,
+            pacer: Pacer::default(),
             available_universes,
+            browser: None,
+            message: None,
+            offset: (0, 0),
+            rule: None,
+            topology: Topology::default(),
+            size: None,
+            orientation: Orientation::default(),
+            editor: None,
+            clipboard: None,
+            output: None,
+            history: History::new(UNDO_BUDGET),
+            renderer: Renderer::default(),
+            theme: Theme::default(),
+            aging: false,
+            heat: None,
+            keymap: Keymap::default(),
+            help: false,
+            generation: 0,
+            target: None,
+            bookmarks: Bookmarks::default(),
+            recording: None,
+            command_line: CommandLine::default(),
+            startup: None,
         }
     }
     pub fn paused(&self) -> bool {
-        self.poll_t == PAUSE
+        self.paused
     }
     pub fn len(&self) -> usize {
         self.available_universes.len() + shapes::N
@@ -67,11 +335,36 @@ impl App {
         self.len() == 0
     }
     pub fn get(&self) -> Universe {
+        self.get_nth(self.i)
+    }
+    fn get_nth(&self, i: usize) -> Universe {
         let true_len = self.available_universes.len();
-        if self.i < true_len {
-            self.available_universes.get(self.i).unwrap().clone()
+        if i < true_len {
+            self.available_universes.get(i).unwrap().clone()
+        } else {
+            // so that it fills the universe once oriented
+            let area = self.universe_area();
+            let area = if self.orientation.swaps_axes() {
+                Area::new(area.height, area.width)
+            } else {
+                area
+            };
+            shapes::get_special(i - true_len, area)
+        }
+    }
+    /// Size of the universe, unless the pattern is even bigger
+    pub fn universe_area(&self) -> Area {
+        self.size.unwrap_or(self.area)
+    }
+    /// Apply a change of the visible area
+    pub fn set_area(&mut self, area: Area) {
+        self.area = area;
+        // a fixed size universe is built once, on the first draw
+        if self.size.is_some() && !self.universe.area.is_empty() {
+            // just keeping the view inside the universe
+            self.scroll(0, 0);
         } else {
-            shapes::get_special(self.i - true_len, self.area)
+            self.restart();
         }
     }
 
@@ -79,40 +372,133 @@ impl App {
     //     println!("{}", self.universe);
     // }
 
-    pub fn play_pause(&mut self, prev_poll_t: &mut Duration) {
-        if self.paused() {
-            self.poll_t = *prev_poll_t;
-        } else {
-            *prev_poll_t = self.poll_t;
-            self.poll_t = PAUSE;
-        }
+    pub fn play_pause(&mut self) {
+        self.paused = !self.paused;
+        self.pacer.reset();
     }
+    /// Restart with the current pattern. If it's bigger than `area`,
+    /// the universe grows to fit it, only part of it being visible.
     pub fn restart(&mut self) {
-        let figur = self.get();
-        self.universe = Universe::from_figur(self.area, figur)
-            .expect("display area should be big enough to fit this figure");
+        let mut figur = self
+            .orientation
+            .apply(&self.get())
+            .with_topology(self.topology);
+        if let Some(rule) = self.rule {
+            figur.rule = rule;
+        }
+        let name = figur.name().to_string();
+        let area = self.universe_area();
+        let area = Area::new(
+            area.width.max(figur.width()),
+            area.height.max(figur.height()),
+        );
+        self.universe = Universe::from_figur(area, figur).unwrap_or_else(|e| {
+            self.message = Some(format!("can't load {name}: {e}"));
+            shapes::empty(area).with_name(name)
+        });
+        // centering the view
+        self.offset = (
+            area.height.saturating_sub(self.area.height) / 2,
+            area.width.saturating_sub(self.area.width) / 2,
+        );
+        if area.is_empty() {
+            // no cell to edit
+            self.editor = None;
+        }
+        if let Some(editor) = self.editor.as_mut() {
+            editor.move_cursor(0, 0, area);
+        }
+        self.history.clear();
+        if let Some(heat) = self.heat.as_mut() {
+            heat.reset();
+        }
+        self.generation = 0;
+    }
+    /// Change the orientation of patterns, then restart.
+    /// An edited board is reoriented in place instead, as an edit that can be undone.
+    pub fn orient(&mut self, f: impl Fn(Orientation) -> Orientation) {
+        if self.history.is_empty() {
+            self.orientation = f(self.orientation);
+            self.restart();
+            return;
+        }
+        let before = self.universe.clone();
+        if let Some((at, area)) = before.bounds() {
+            let turned = f(Orientation::default()).apply(&before.crop(at, area));
+            let board = before.area;
+            if !board.fits(turned.area) {
+                let e = Error::TooBig {
+                    figure: turned.area,
+                    area: board,
+                };
+                self.message = Some(format!("can't reorient: {e}"));
+                return;
+            }
+            // around the same centre, inside the board
+            let place = |start: u16, len: u16, new: u16, max: u16| {
+                (start + len / 2).saturating_sub(new / 2).min(max - new)
+            };
+            let at = (
+                place(at.0, area.height, turned.height(), board.height),
+                place(at.1, area.width, turned.width(), board.width),
+            );
+            self.universe.fill((0, 0), board, Cell::Dead);
+            self.universe.stamp(at, &turned);
+            self.history.record(&before, &self.universe);
+            self.universe.reset_ages(&before);
+        }
+        self.orientation = f(self.orientation);
+    }
+    /// Whether the universe is bigger than what can be seen of it
+    pub fn scrollable(&self) -> bool {
+        !self.area.fits(self.universe.area)
+    }
+    /// Move the view by `rows` and `cols`, staying inside the universe
+    pub fn scroll(&mut self, rows: i32, cols: i32) {
+        let max_row = self.universe.height().saturating_sub(self.area.height);
+        let max_col = self.universe.width().saturating_sub(self.area.width);
+        let (row, col) = self.offset;
+        self.offset = (
+            (i32::from(row) + rows).clamp(0, max_row.into()) as u16,
+            (i32::from(col) + cols).clamp(0, max_col.into()) as u16,
+        );
     }
 
     pub fn tick(&mut self) {
         self.universe.tick();
+        self.generation += 1;
+        if let Some(heat) = self.heat.as_mut() {
+            heat.record(&self.universe);
+        }
+    }
+    /// Show the heat map, or hide it if shown
+    pub fn toggle_heat(&mut self) {
+        if self.heat.take().is_none() {
+            self.heat = Some(HeatMap::new(HEAT_GENERATIONS));
+        }
+    }
+    /// Run at most `n` generations, stopping early if `budget` runs out.
+    /// Returns the number of generations run.
+    pub fn tick_n(&mut self, n: u64, budget: Duration) -> u64 {
+        let start = Instant::now();
+        let mut ran = 0;
+        while ran < n && start.elapsed() < budget {
+            self.tick();
+            ran += 1;
+        }
+        ran
     }
 
     pub fn faster(&mut self, big: bool) {
         if !self.paused() {
-            let div = if big { 2 } else { 5 };
-            self.poll_t = self
-                .poll_t
-                .checked_sub(self.poll_t.checked_div(div).unwrap_or(DEF_DUR))
-                .unwrap_or(DEF_DUR);
+            let mul = if big { 2. } else { 1.25 };
+            self.speed = (self.speed * mul).min(MAX_SPEED);
         }
     }
     pub fn slower(&mut self, big: bool) {
         if !self.paused() {
-            let div = if big { 2 } else { 5 };
-            self.poll_t = self
-                .poll_t
-                .checked_add(self.poll_t.checked_div(div).unwrap_or(DEF_DUR))
-                .unwrap_or(DEF_DUR);
+            let div = if big { 1.5 } else { 1.2 };
+            self.speed = (self.speed / div).max(MIN_SPEED);
         }
     }
 
@@ -132,38 +518,700 @@ impl App {
         }
         self.restart();
     }
+
+    /// Open the pattern browser, or close it if it's open
+    pub fn toggle_browser(&mut self) {
+        self.bookmarks.panel = None;
+        if self.browser.take().is_none() {
+            let entries = (0..self.len())
+                .map(|i| browser::Entry::new(i, &self.get_nth(i)))
+                .collect();
+            self.browser = Some(Browser::new(entries, self.i));
+        }
+    }
+    /// Open the bookmarks panel, or close it if it's open
+    pub fn toggle_bookmarks(&mut self) {
+        self.browser = None;
+        self.bookmarks.toggle();
+    }
+    /// Bookmark the universe as it is now
+    pub fn bookmark(&mut self) {
+        self.bookmarks.add(self.generation, self.universe.clone());
+        self.message = Some(format!("bookmarked generation {}", self.generation));
+    }
+    /// Go back to the bookmark selected in the panel
+    pub fn load_bookmark(&mut self) {
+        let Some(mark) = self.bookmarks.selected() else {
+            return;
+        };
+        let before = std::mem::replace(&mut self.universe, mark.universe.clone());
+        self.generation = mark.generation;
+        self.target = None;
+        // going back can be undone, the edits made since are kept
+        self.history.record(&before, &self.universe);
+        if let Some(heat) = self.heat.as_mut() {
+            heat.reset();
+        }
+        if let Some(editor) = self.editor.as_mut() {
+            editor.move_cursor(0, 0, self.universe.area);
+        }
+        // keeping the view inside the universe
+        self.scroll(0, 0);
+    }
+    /// Save the bookmarks side by side to `path`, in the format of its extension, plaintext by default
+    pub fn save_bookmarks(&mut self, path: &Path) {
+        if self.bookmarks.list.is_empty() {
+            self.message = Some("no bookmarks to save".into());
+            return;
+        }
+        let format = format::Format::from_path(path).unwrap_or_default();
+        let (pattern, comments) = self.bookmarks.to_pattern();
+        let text = format.write_commented(&pattern, &comments);
+        self.message = Some(match std::fs::write(path, text) {
+            Ok(()) => format!(
+                "saved {} bookmarks to {}",
+                self.bookmarks.list.len(),
+                path.display()
+            ),
+            Err(e) => format!("can't save to {}: {e}", path.display()),
+        });
+    }
+    /// Start stamping the pattern selected in the browser onto the board, closing the browser
+    pub fn stamp_selected(&mut self) {
+        if let Some(entry) = self.browser.take().as_ref().and_then(Browser::selected) {
+            let figur = self.get_nth(entry.i).with_topology(self.topology);
+            self.paste_pattern(Paste::new(figur.trim(), true));
+        }
+    }
+    /// Load the pattern selected in the browser
+    pub fn load_selected(&mut self) {
+        if let Some(entry) = self.browser.as_ref().and_then(Browser::selected) {
+            self.i = entry.i;
+            self.restart();
+        }
+    }
+    /// Typing the search query of the browser
+    fn search_key(&mut self, code: KeyCode) {
+        let Some(browser) = self.browser.as_mut() else {
+            return;
+        };
+        match code {
+            KeyCode::Esc => {
+                browser.clear();
+                browser.searching = false;
+            }
+            KeyCode::Enter => browser.searching = false,
+            KeyCode::Backspace => browser.pop(),
+            KeyCode::Char(ch) => browser.push(ch),
+            KeyCode::Down => browser.select_next(),
+            KeyCode::Up => browser.select_prev(),
+            _ => {}
+        }
+    }
+
+    /// Typing a command
+    fn command_key(&mut self, code: KeyCode) {
+        let line = &mut self.command_line;
+        match code {
+            KeyCode::Esc => line.close(),
+            KeyCode::Enter => {
+                let command = line.enter();
+                if !command.trim().is_empty() {
+                    self.execute(&command);
+                }
+            }
+            KeyCode::Backspace => line.pop(),
+            KeyCode::Tab => line.complete(),
+            KeyCode::Up => line.prev(),
+            KeyCode::Down => line.next(),
+            KeyCode::Char(ch) => line.push(ch),
+            _ => {}
+        }
+    }
+    /// Parse and run `command`, showing what went wrong if anything
+    pub fn execute(&mut self, command: &str) {
+        match command.parse() {
+            Ok(command) => self.run_command(command),
+            Err(e) => self.message = Some(e.to_string()),
+        }
+    }
+    pub fn run_command(&mut self, command: Command) {
+        match command {
+            Command::Rule(rule) => {
+                self.rule = Some(rule);
+                self.universe.rule = rule;
+            }
+            Command::Goto(generation) => self.goto(generation),
+            Command::Load(path) => self.load(&path),
+            Command::Save(path) => self.save(&path),
+            Command::SaveBookmarks(path) => self.save_bookmarks(&path),
+            Command::Record(path) => self.start_recording(&path),
+            Command::Seed(seed) => {
+                fastrand::seed(seed);
+                self.restart();
+            }
+            Command::Size(size) => {
+                self.size = size;
+                self.restart();
+            }
+        }
+    }
+    /// Remember the patterns and settings as they are now for resets, unless they already are
+    fn remember_startup(&mut self) {
+        self.startup.get_or_insert_with(|| Startup {
+            universes: self.available_universes.clone(),
+            i: self.i,
+            speed: self.speed,
+            paused: self.paused,
+            rule: self.rule,
+            topology: self.topology,
+            size: self.size,
+            renderer: self.renderer,
+            theme: self.theme,
+            aging: self.aging,
+        });
+    }
+    /// Go back to the patterns and settings the app was started with, forgetting everything else
+    /// but the keys
+    pub fn reset(&mut self) {
+        // the GIF being recorded is finished, telling where it is
+        self.stop_recording();
+        let app = Self {
+            keymap: std::mem::take(&mut self.keymap),
+            message: self.message.take(),
+            startup: self.startup.take(),
+            ..Self::default()
+        };
+        *self = match app.startup.clone() {
+            Some(start) => Self {
+                available_universes: start.universes,
+                i: start.i,
+                speed: start.speed,
+                paused: start.paused,
+                rule: start.rule,
+                topology: start.topology,
+                size: start.size,
+                renderer: start.renderer,
+                theme: start.theme,
+                aging: start.aging,
+                ..app
+            },
+            None => app,
+        };
+    }
+    /// Start jumping to `generation`, restarting first if it's already past
+    pub fn goto(&mut self, generation: u64) {
+        if generation < self.generation {
+            self.restart();
+        }
+        self.target = Some(generation);
+    }
+    /// Run towards the generation being jumped to until `budget` runs out
+    pub fn fast_forward(&mut self, budget: Duration) {
+        let Some(target) = self.target else {
+            return;
+        };
+        let left = target.saturating_sub(self.generation);
+        if self.tick_n(left, budget) == left {
+            self.target = None;
+        }
+    }
+    /// Stop jumping, staying at the generation reached
+    pub fn cancel_jump(&mut self) {
+        if let Some(target) = self.target.take() {
+            self.message = Some(format!(
+                "stopped at generation {} of {target}",
+                self.generation
+            ));
+        }
+    }
+    /// Load the patterns at `path`, starting with the first one
+    pub fn load(&mut self, path: &Path) {
+        let (universes, errors): (Vec<_>, Vec<_>) =
+            load::load(path).into_iter().partition(Result::is_ok);
+        let universes = universes.into_iter().flatten().collect::<Vec<_>>();
+        let errors = errors
+            .into_iter()
+            .filter_map(Result::err)
+            .collect::<Vec<_>>();
+        let mut message = match universes.as_slice() {
+            [univ] => format!("loaded {}", univ.name()),
+            universes => format!("loaded {} patterns", universes.len()),
+        };
+        match errors.as_slice() {
+            [] => {}
+            [e] if universes.is_empty() => message = format!("can't load {e}"),
+            [e, ..] => message += &format!(", failed to load {}, eg. {e}", errors.len()),
+        }
+        self.message = Some(message);
+        if !universes.is_empty() {
+            self.i = self.available_universes.len();
+            self.available_universes.extend(universes);
+            self.restart();
+        }
+    }
+    /// Save the live part of the board to `path`, in the format of its extension, plaintext by default
+    pub fn save(&mut self, path: &Path) {
+        let format = format::Format::from_path(path).unwrap_or_default();
+        let figur = self.universe.trim();
+        self.message = Some(match std::fs::write(path, format.write(&figur)) {
+            Ok(()) => format!(
+                "saved {}×{} to {}",
+                figur.width(),
+                figur.height(),
+                path.display()
+            ),
+            Err(e) => format!("can't save to {}: {e}", path.display()),
+        });
+    }
+
+    /// Start recording a GIF named after the pattern, or stop if recording
+    pub fn toggle_recording(&mut self) {
+        if self.recording.is_some() {
+            self.stop_recording();
+            return;
+        }
+        let name = self
+            .universe
+            .name()
+            .chars()
+            .map(|c| {
+                if c.is_alphanumeric() {
+                    c.to_ascii_lowercase()
+                } else {
+                    '-'
+                }
+            })
+            .collect::<String>();
+        let path = (0..)
+            .map(|i| match i {
+                0 => PathBuf::from(format!("{name}.gif")),
+                i => PathBuf::from(format!("{name}-{i}.gif")),
+            })
+            .find(|path| !path.exists())
+            .expect("infinitely many paths");
+        self.start_recording(&path);
+    }
+    /// Start recording a GIF to `path` of the generations drawn, in the colours of the theme
+    pub fn start_recording(&mut self, path: &Path) {
+        if self.recording.is_some() {
+            self.stop_recording();
+        }
+        let style = record::Style::from_theme(self.theme);
+        let recorder = File::create(path)
+            .map_err(Error::from)
+            .and_then(|file| Recorder::new(BufWriter::new(file), self.universe.area, style));
+        match recorder {
+            Ok(recorder) => {
+                self.recording = Some(Recording::new(path.to_path_buf(), recorder));
+                self.message = Some(format!("recording to {}", path.display()));
+                self.record_frame();
+            }
+            Err(e) => self.message = Some(format!("can't record to {}: {e}", path.display())),
+        }
+    }
+    /// Finish the GIF being recorded
+    pub fn stop_recording(&mut self) {
+        let Some(recording) = self.recording.take() else {
+            return;
+        };
+        let frames = recording.frames();
+        let path = recording.path.clone();
+        self.message = Some(
+            match recording.finish().and_then(|mut out| Ok(out.flush()?)) {
+                Ok(()) => format!("recorded {frames} generations to {}", path.display()),
+                Err(e) => format!("can't record to {}: {e}", path.display()),
+            },
+        );
+    }
+    /// Add the generation drawn to the GIF being recorded, if it's a new one
+    fn record_frame(&mut self) {
+        // generations jumped over aren't recorded
+        if self.target.is_some() {
+            return;
+        }
+        let Some(recording) = self.recording.as_mut() else {
+            return;
+        };
+        if let Err(e) = recording.show(self.generation, &self.universe) {
+            self.message = Some(format!("can't record to {}: {e}", recording.path.display()));
+            self.recording = None;
+        }
+    }
+
+    /// Enter edit mode, pausing, or leave it, an empty universe can't be edited
+    pub fn toggle_editor(&mut self) {
+        if self.editor.take().is_none() && !self.universe.area.is_empty() {
+            if !self.paused {
+                self.play_pause();
+            }
+            let (row, col) = self.offset;
+            let visible = Area::new(
+                self.area.width.min(self.universe.width()),
+                self.area.height.min(self.universe.height()),
+            );
+            let center = (row + visible.height / 2, col + visible.width / 2);
+            self.editor = Some(Editor::new(center));
+        }
+    }
+    /// Scroll so that the cursor is visible
+    fn follow_cursor(&mut self) {
+        let Some(editor) = &self.editor else {
+            return;
+        };
+        let (row, col) = editor.cursor;
+        let (off_row, off_col) = self.offset;
+        let follow = |cur: u16, off: u16, len: u16| {
+            if cur < off {
+                cur
+            } else if cur >= off + len {
+                cur + 1 - len
+            } else {
+                off
+            }
+        };
+        self.offset = (
+            follow(row, off_row, self.area.height),
+            follow(col, off_col, self.area.width),
+        );
+    }
+    /// Copy the selection to the clipboard
+    pub fn copy(&mut self) {
+        let Some((at, area)) = self.editor.as_ref().and_then(Editor::selection) else {
+            self.message = Some(self.missing("nothing selected", Action::Select, "select"));
+            return;
+        };
+        let name = format!("part of {}", self.universe.name());
+        self.clipboard = Some(self.universe.crop(at, area).with_name(name));
+    }
+    /// Set every cell of the selection to `cell`
+    pub fn fill(&mut self, cell: Cell) {
+        match self.editor.as_ref().and_then(Editor::selection) {
+            Some((at, area)) => self.universe.fill(at, area, cell),
+            None => {
+                self.message = Some(self.missing("nothing selected", Action::Select, "select"));
+            }
+        }
+    }
+    /// Start pasting the clipboard at the cursor
+    pub fn start_paste(&mut self) {
+        match self.clipboard.clone() {
+            Some(clipboard) => self.paste_pattern(Paste::new(clipboard, false)),
+            None => self.message = Some(self.missing("clipboard is empty", Action::Copy, "copy")),
+        }
+    }
+    /// `what` is missing, followed by the key of the edit `action` that provides it if bound
+    fn missing(&self, what: &str, action: Action, verb: &str) -> String {
+        match self.keymap.hint(Mode::Edit, &[action]) {
+            Some(key) => format!("{what}: {key} to {verb}"),
+            None => what.into(),
+        }
+    }
+    /// Start pasting at the cursor, entering edit mode if needed
+    pub fn paste_pattern(&mut self, paste: Paste) {
+        if self.editor.is_none() {
+            self.toggle_editor();
+        }
+        if let Some(editor) = self.editor.as_mut() {
+            editor.anchor = None;
+            editor.pasting = Some(paste);
+        }
+    }
+    /// Start pasting a pattern given as text: RLE, plaintext or Life 1.06
+    pub fn paste_text(&mut self, text: &str) {
+        match format::parse(text) {
+            Ok(figur) => self.paste_pattern(Paste::new(figur, false)),
+            Err(e) => self.message = Some(format!("can't paste: {e}")),
+        }
+    }
+    /// Copy the selection, or the whole board if nothing is selected, to the system clipboard as RLE
+    pub fn copy_rle(&mut self) {
+        let figur = match self.editor.as_ref().and_then(Editor::selection) {
+            Some((at, area)) => self.universe.crop(at, area),
+            None => self.universe.trim(),
+        };
+        self.output = Some(clipboard::osc52(&format::to_rle(&figur)));
+        self.message = Some(format!(
+            "copied {}×{} to the clipboard as RLE",
+            figur.width(),
+            figur.height()
+        ));
+    }
+    /// Place the pattern being pasted onto the board
+    pub fn place(&mut self) {
+        if let Some(editor) = self.editor.as_mut() {
+            let Some(figur) = editor.preview() else {
+                return;
+            };
+            if editor.pasting.as_ref().is_some_and(|paste| paste.stamp) {
+                self.universe.stamp(editor.cursor, &figur);
+            } else {
+                self.universe.paste(editor.cursor, &figur);
+                editor.pasting = None;
+            }
+        }
+    }
+    /// Revert the last edit of the board
+    pub fn undo(&mut self) {
+        if !self.history.undo(&mut self.universe) {
+            self.message = Some("nothing to undo".into());
+        }
+    }
+    /// Apply the last undone edit of the board again
+    pub fn redo(&mut self) {
+        if !self.history.redo(&mut self.universe) {
+            self.message = Some("nothing to redo".into());
+        }
+    }
+    /// Move the cursor of the editor by `rows` and `cols`
+    fn move_cursor(&mut self, rows: i32, cols: i32) {
+        let area = self.universe.area;
+        if let Some(editor) = self.editor.as_mut() {
+            editor.move_cursor(rows, cols, area);
+        }
+    }
+    /// Change the orientation of the pattern being pasted if pasting, otherwise of the patterns
+    fn orient_any(&mut self, f: impl Fn(Orientation) -> Orientation) {
+        match self.editor.as_mut() {
+            Some(editor) if editor.pasting.is_some() => editor.orient(f),
+            _ => self.orient(f),
+        }
+    }
+    /// Which keys are active
+    pub fn mode(&self) -> Mode {
+        match &self.editor {
+            _ if self.browser.is_some() => Mode::Browser,
+            _ if self.bookmarks.panel.is_some() => Mode::Bookmarks,
+            Some(editor) if editor.pasting.is_some() => Mode::Paste,
+            Some(_) => Mode::Edit,
+            None => Mode::Normal,
+        }
+    }
+
+    /// # Returns
+    ///
+    /// whether to keep on running
+    fn perform(&mut self, action: Action) -> bool {
+        // edits of the board can be undone
+        let editing = self.editor.is_some() && action.edits();
+        let before = editing.then(|| self.universe.clone());
+        let keep_running = self.act(action);
+        if let Some(before) = before {
+            self.history.record(&before, &self.universe);
+            self.universe.reset_ages(&before);
+        }
+        self.follow_cursor();
+        keep_running
+    }
+    fn act(&mut self, action: Action) -> bool {
+        match action {
+            Action::Quit => return false,
+            Action::Slower => self.slower(false),
+            Action::Faster => self.faster(false),
+            Action::PlayPause => self.play_pause(),
+            Action::Restart => self.restart(),
+            Action::NextPattern => self.next(),
+            Action::PrevPattern => self.prev(),
+            Action::Browse => self.toggle_browser(),
+            Action::Edit => self.toggle_editor(),
+            Action::ScrollLeft => self.scroll(0, -SCROLL),
+            Action::ScrollDown => self.scroll(SCROLL, 0),
+            Action::ScrollUp => self.scroll(-SCROLL, 0),
+            Action::ScrollRight => self.scroll(0, SCROLL),
+            Action::RotateRight => self.orient_any(|o| o.rotate(1)),
+            Action::RotateLeft => self.orient_any(|o| o.rotate(3)),
+            Action::FlipHorizontal => self.orient_any(Orientation::flip_horizontal),
+            Action::FlipVertical => self.orient_any(Orientation::flip_vertical),
+            Action::Transpose => self.orient_any(Orientation::transpose),
+            Action::CommandLine => self.command_line.open(),
+            Action::Goto => {
+                self.command_line.open();
+                self.command_line.input = "goto ".into();
+            }
+            Action::Bookmark => self.bookmark(),
+            Action::Record => self.toggle_recording(),
+            Action::Bookmarks => self.toggle_bookmarks(),
+            Action::Delete => self.bookmarks.delete_selected(),
+            Action::SaveBookmarks => {
+                self.command_line.open();
+                self.command_line.input = "bookmarks ".into();
+            }
+            Action::Reset => self.reset(),
+            Action::CopyRle => self.copy_rle(),
+            Action::NextRenderer => self.renderer = self.renderer.next(),
+            Action::NextTheme => self.theme = self.theme.next(),
+            Action::ToggleAge => self.aging = !self.aging,
+            Action::ToggleHeat => self.toggle_heat(),
+            Action::ResetHeat => {
+                if let Some(heat) = self.heat.as_mut() {
+                    heat.reset();
+                }
+            }
+            Action::Undo => self.undo(),
+            Action::Redo => self.redo(),
+            Action::Help => self.help = true,
+            Action::Left => self.move_cursor(0, -1),
+            Action::Down => self.move_cursor(1, 0),
+            Action::Up => self.move_cursor(-1, 0),
+            Action::Right => self.move_cursor(0, 1),
+            Action::FarLeft => self.move_cursor(0, -SCROLL),
+            Action::FarDown => self.move_cursor(SCROLL, 0),
+            Action::FarUp => self.move_cursor(-SCROLL, 0),
+            Action::FarRight => self.move_cursor(0, SCROLL),
+            Action::ToggleCell => {
+                if let Some(editor) = &self.editor {
+                    let cell = &mut self.universe[editor.cursor];
+                    *cell = match cell {
+                        Cell::Alive => Cell::Dead,
+                        Cell::Dead => Cell::Alive,
+                    };
+                }
+            }
+            Action::Select => {
+                if let Some(editor) = self.editor.as_mut() {
+                    editor.toggle_selection();
+                }
+            }
+            Action::Copy => self.copy(),
+            Action::Cut => {
+                self.copy();
+                self.fill(Cell::Dead);
+            }
+            Action::Clear => self.fill(Cell::Dead),
+            Action::Fill => self.fill(Cell::Alive),
+            Action::Paste => self.start_paste(),
+            Action::Place => self.place(),
+            Action::Back => match self.mode() {
+                Mode::Browser => self.browser = None,
+                Mode::Bookmarks => self.bookmarks.panel = None,
+                Mode::Normal => {}
+                Mode::Paste | Mode::Edit => {
+                    let editor = self.editor.as_mut().expect("editing");
+                    if editor.pasting.take().is_none() && editor.anchor.take().is_none() {
+                        self.editor = None;
+                    }
+                }
+            },
+            Action::Search => {
+                if let Some(browser) = self.browser.as_mut() {
+                    browser.searching = true;
+                }
+            }
+            Action::SelectNext if self.mode() == Mode::Bookmarks => self.bookmarks.select_next(),
+            Action::SelectNext => {
+                if let Some(browser) = self.browser.as_mut() {
+                    browser.select_next();
+                }
+            }
+            Action::SelectPrev if self.mode() == Mode::Bookmarks => self.bookmarks.select_prev(),
+            Action::SelectPrev => {
+                if let Some(browser) = self.browser.as_mut() {
+                    browser.select_prev();
+                }
+            }
+            Action::Load if self.mode() == Mode::Bookmarks => self.load_bookmark(),
+            Action::Load => self.load_selected(),
+            Action::Stamp => self.stamp_selected(),
+        }
+        true
+    }
+    /// # Returns
+    ///
+    /// whether to keep on running
+    fn handle_key(&mut self, key: KeyEvent) -> bool {
+        self.message = None;
+        if self.help {
+            // any key closes it
+            self.help = false;
+            return true;
+        }
+        if self.command_line.active {
+            self.command_key(key.code);
+            return true;
+        }
+        if self
+            .browser
+            .as_ref()
+            .is_some_and(|browser| browser.searching)
+        {
+            self.search_key(key.code);
+            return true;
+        }
+        match self.keymap.action(self.mode(), key.into()) {
+            // quitting or going back cancels a jump first
+            Some(Action::Quit | Action::Back) if self.target.is_some() => {
+                self.cancel_jump();
+                true
+            }
+            Some(action) => self.perform(action),
+            None => true,
+        }
+    }
+
     pub fn run<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> io::Result<()> {
-        let mut prev_poll_t = self.poll_t;
+        self.remember_startup();
+        let mut last_frame = Instant::now();
+        // whether anything changed since the last frame drawn
+        let mut redraw = true;
 
         loop {
-            terminal.draw(|f| ui::ui(f, self))?;
+            if redraw {
+                terminal.draw(|f| ui::ui(f, self))?;
+                self.record_frame();
+                redraw = false;
+            }
+            if let Some(output) = self.output.take() {
+                let mut stdout = io::stdout();
+                stdout.write_all(output.as_bytes())?;
+                stdout.flush()?;
+            }
 
-            // Wait up to `poll_t` for another event
-            if event::poll(self.poll_t)? {
-                if let Event::Key(key) = event::read()? {
-                    if key.kind != KeyEventKind::Press {
-                        continue;
+            // Wait for another event until the next frame is due
+            let timeout = if self.target.is_some() {
+                Duration::ZERO
+            } else if self.paused() {
+                PAUSE
+            } else {
+                FRAME.saturating_sub(last_frame.elapsed())
+            };
+            if event::poll(timeout)? {
+                redraw = true;
+                match event::read()? {
+                    Event::Key(key) if key.kind == KeyEventKind::Press && !self.handle_key(key) => {
+                        break;
                     }
-                    match key.code {
-                        KeyCode::Char('q') | KeyCode::Esc => break,
-                        KeyCode::Char('j') | KeyCode::Down => self.slower(false),
-                        KeyCode::Char('k') | KeyCode::Up => self.faster(false),
-                        KeyCode::Char(' ') | KeyCode::Enter => self.play_pause(&mut prev_poll_t),
-                        KeyCode::Char('r') => self.restart(),
-                        KeyCode::Char('n' | 'l') | KeyCode::Right => self.next(),
-                        KeyCode::Char('p' | 'h') | KeyCode::Left => self.prev(),
-                        KeyCode::Char('R') | KeyCode::Backspace => *self = Self::default(),
-                        _ => {}
+                    Event::Paste(text) => {
+                        self.message = None;
+                        self.paste_text(&text);
                     }
-                } else {
                     // resize and restart
-                    self.restart();
+                    Event::Resize(..) => self.set_area(self.area),
+                    _ => {}
                 }
-            } else {
-                // Timeout expired, updating life state
-                self.tick();
+            }
+            if self.target.is_some() {
+                // no generations owed for the time spent jumping
+                self.fast_forward(FRAME);
+                last_frame = Instant::now();
+                self.pacer.reset();
+                redraw = true;
+            } else if self.paused() {
+                last_frame = Instant::now();
+            } else if last_frame.elapsed() >= FRAME {
+                // Frame is due, updating life state: running as many generations
+                // as fit into one frame, skipping the rendering of the ones in between
+                let elapsed = last_frame.elapsed();
+                last_frame = Instant::now();
+                let due = self.pacer.due(elapsed, self.speed);
+                let ran = self.tick_n(due, FRAME);
+                if ran < due {
+                    // can't keep up: don't pile up owed generations
+                    self.pacer.reset();
+                }
+                redraw |= ran > 0;
             }
         }
+        self.stop_recording();
 
         Ok(())
     }
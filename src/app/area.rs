@@ -14,6 +14,29 @@ impl Area {
     pub const fn is_empty(&self) -> bool {
         self.len() == 0
     }
+    /// Whether `other` fits into `self`, both horizontally and vertically
+    pub const fn fits(&self, other: Area) -> bool {
+        other.width <= self.width && other.height <= self.height
+    }
+}
+impl std::str::FromStr for Area {
+    type Err = super::Error;
+
+    /// Parse `<width>x<height>`, eg. 200x100, neither can be 0
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || super::Error::Invalid {
+            what: "size",
+            value: s.to_string(),
+        };
+        let (width, height) = s.trim().split_once(['x', 'X', '×']).ok_or_else(invalid)?;
+        let width = width.trim().parse().map_err(|_| invalid())?;
+        let height = height.trim().parse().map_err(|_| invalid())?;
+        let area = Self::new(width, height);
+        if area.is_empty() {
+            return Err(invalid());
+        }
+        Ok(area)
+    }
 }
 impl<U1: Into<u16>, U2: Into<u16>> From<(U1, U2)> for Area {
     fn from(val: (U1, U2)) -> Self {
@@ -0,0 +1,101 @@
+use super::{shapes, Area, Universe};
+use ratatui::widgets::ListState;
+
+/// Dead columns between bookmarks saved side by side
+const GAP: u16 = 4;
+
+/// The universe as it was at a generation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bookmark {
+    pub generation: u64,
+    pub universe: Universe,
+}
+
+/// Bookmarks of the running universe, with their side panel
+#[derive(Debug, Default)]
+pub struct Bookmarks {
+    /// in the order they were made
+    pub list: Vec<Bookmark>,
+    /// selection of the side panel, if open
+    pub panel: Option<ListState>,
+}
+impl Bookmarks {
+    /// Bookmark `universe` at `generation`, selecting it
+    pub fn add(&mut self, generation: u64, universe: Universe) {
+        self.list.push(Bookmark {
+            generation,
+            universe,
+        });
+        if let Some(panel) = self.panel.as_mut() {
+            panel.select(Some(self.list.len() - 1));
+        }
+    }
+    /// Open the side panel with the latest bookmark selected, or close it if it's open
+    pub fn toggle(&mut self) {
+        if self.panel.take().is_none() {
+            let latest = self.list.len().checked_sub(1);
+            self.panel = Some(ListState::default().with_selected(latest));
+        }
+    }
+    /// The bookmark selected in the panel
+    pub fn selected(&self) -> Option<&Bookmark> {
+        self.list.get(self.panel.as_ref()?.selected()?)
+    }
+    pub fn select_next(&mut self) {
+        if let Some(panel) = self.panel.as_mut() {
+            panel.select_next();
+        }
+    }
+    pub fn select_prev(&mut self) {
+        if let Some(panel) = self.panel.as_mut() {
+            panel.select_previous();
+        }
+    }
+    /// Delete the bookmark selected in the panel
+    pub fn delete_selected(&mut self) {
+        let Some(panel) = self.panel.as_mut() else {
+            return;
+        };
+        if let Some(i) = panel.selected().filter(|&i| i < self.list.len()) {
+            self.list.remove(i);
+            panel.select(self.list.len().checked_sub(1).map(|last| i.min(last)));
+        }
+    }
+    /// The live parts of all the bookmarks side by side, left to right,
+    /// with comments telling the generation of each and where it is
+    pub fn to_pattern(&self) -> (Universe, Vec<String>) {
+        let figures = self
+            .list
+            .iter()
+            .map(|mark| mark.universe.trim())
+            .collect::<Vec<_>>();
+        let width = figures
+            .iter()
+            .fold(0, |width: u16, f| width.saturating_add(f.width() + GAP));
+        let height = figures.iter().map(Universe::height).max().unwrap_or(0);
+        let area = Area::new(width.saturating_sub(GAP), height);
+        let name = self
+            .list
+            .first()
+            .map(|mark| format!("Bookmarks of {}", mark.universe.name()))
+            .unwrap_or_default();
+        let mut pattern = shapes::empty(area).with_name(name);
+        if let Some(mark) = self.list.first() {
+            pattern.rule = mark.universe.rule;
+        }
+        let mut comments = vec![];
+        let mut col = 0;
+        for (mark, figur) in self.list.iter().zip(&figures) {
+            pattern.paste((0, col), figur);
+            comments.push(format!(
+                "generation {} at column {col}: {}×{}, {} alive",
+                mark.generation,
+                figur.width(),
+                figur.height(),
+                figur.population()
+            ));
+            col += figur.width() + GAP;
+        }
+        (pattern, comments)
+    }
+}
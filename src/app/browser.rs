@@ -0,0 +1,132 @@
+use super::{Area, Universe};
+use ratatui::widgets::ListState;
+
+/// A pattern as listed in the `Browser`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// index of the pattern in `App`
+    pub i: usize,
+    pub name: String,
+    pub area: Area,
+    pub population: usize,
+}
+impl Entry {
+    pub fn new(i: usize, univ: &Universe) -> Self {
+        Self {
+            i,
+            name: univ.name().to_string(),
+            area: univ.area,
+            population: univ.population(),
+        }
+    }
+}
+
+/// Side panel listing all the patterns, searchable by name
+#[derive(Debug, Default)]
+pub struct Browser {
+    pub entries: Vec<Entry>,
+    /// fuzzy search query
+    pub query: String,
+    /// whether the query is being typed
+    pub searching: bool,
+    pub state: ListState,
+}
+impl Browser {
+    pub fn new(entries: Vec<Entry>, selected: usize) -> Self {
+        let mut browser = Self {
+            entries,
+            ..Default::default()
+        };
+        let selected = browser.matches().iter().position(|e| e.i == selected);
+        browser.state.select(selected.or(Some(0)));
+        browser
+    }
+
+    /// Entries matching the query, best match first
+    pub fn matches(&self) -> Vec<&Entry> {
+        let mut matches = self
+            .entries
+            .iter()
+            .filter_map(|e| fuzzy_score(&self.query, &e.name).map(|score| (score, e)))
+            .collect::<Vec<_>>();
+        // stable: equally good matches keep their order
+        matches.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        matches.into_iter().map(|(_, e)| e).collect()
+    }
+    /// The currently selected entry
+    pub fn selected(&self) -> Option<&Entry> {
+        self.matches().get(self.state.selected()?).copied()
+    }
+
+    pub fn select_next(&mut self) {
+        let len = self.matches().len();
+        let i = self.state.selected().map_or(0, |i| (i + 1) % len.max(1));
+        self.state.select(Some(i));
+    }
+    pub fn select_prev(&mut self) {
+        let len = self.matches().len();
+        let i = self
+            .state
+            .selected()
+            .map_or(0, |i| (i + len.max(1) - 1) % len.max(1));
+        self.state.select(Some(i));
+    }
+
+    pub fn push(&mut self, ch: char) {
+        self.query.push(ch);
+        self.state.select(Some(0));
+    }
+    pub fn pop(&mut self) {
+        self.query.pop();
+        self.state.select(Some(0));
+    }
+    pub fn clear(&mut self) {
+        self.query.clear();
+        self.state.select(Some(0));
+    }
+}
+
+/// Score how well `name` matches `query`: every character of `query` has to be
+/// found in `name` in order, ignoring case.
+/// Consecutive matches and matches at the start of words, especially the first one score higher.
+///
+/// # Returns
+///
+/// `None` if `name` doesn't match
+pub fn fuzzy_score(query: &str, name: &str) -> Option<i32> {
+    let name = name.to_lowercase().chars().collect::<Vec<_>>();
+    let query = query
+        .to_lowercase()
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<Vec<_>>();
+    let Some(first) = query.first() else {
+        return Some(0);
+    };
+    // try every occurrence of the first character, keep the best
+    (0..name.len())
+        .filter(|&start| name[start] == *first)
+        .filter_map(|start| score_from(&query, &name, start))
+        .max()
+}
+/// Greedily match `query` in `name`, the first character being at `start`
+fn score_from(query: &[char], name: &[char], start: usize) -> Option<i32> {
+    let mut score = 0;
+    let mut from = start;
+    let mut prev = None;
+    for q in query {
+        let found = from + name[from..].iter().position(|c| c == q)?;
+        score += 1;
+        if prev.is_some_and(|prev| prev + 1 == found) {
+            score += 5;
+        }
+        if found == 0 {
+            score += 5;
+        } else if !name[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+        prev = Some(found);
+        from = found + 1;
+    }
+    Some(score)
+}
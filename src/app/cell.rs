@@ -1,5 +1,5 @@
 /// information about one `Cell`: either `Dead` or `Alive`
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
 pub enum Cell {
     #[default]
     Dead = 0,
@@ -15,15 +15,13 @@ impl From<bool> for Cell {
     }
 }
 impl TryFrom<char> for Cell {
-    type Error = String;
+    type Error = super::Error;
 
     fn try_from(ch: char) -> Result<Self, Self::Error> {
         match ch {
             'O' => Ok(Cell::Alive),
             '.' => Ok(Cell::Dead),
-            _ => Err(format!(
-                "parse error: {ch:?} is an invalid character, should be either '.' or 'O'"
-            )),
+            _ => Err(super::Error::InvalidCell(ch)),
         }
     }
 }
@@ -0,0 +1,241 @@
+use super::{load, Area, Error, Rule};
+use std::path::{Path, PathBuf};
+
+/// The commands with their arguments, for completion and errors
+pub const COMMANDS: [(&str, &str); 8] = [
+    ("rule", "<B/S>"),
+    ("goto", "<GENERATION>"),
+    ("load", "<PATH>"),
+    ("save", "<PATH>"),
+    ("bookmarks", "<PATH>"),
+    ("record", "<PATH>"),
+    ("seed", "<NUMBER>"),
+    ("size", "<WIDTH>x<HEIGHT>|auto"),
+];
+
+/// What can be done from the command line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// use this rule from now on
+    Rule(Rule),
+    /// run until this generation, restarting if it's already past
+    Goto(u64),
+    /// load a pattern file or directory, start with the first one
+    Load(PathBuf),
+    /// save the board, its format guessed from the extension
+    Save(PathBuf),
+    /// save the bookmarks side by side, like `Save`
+    SaveBookmarks(PathBuf),
+    /// record the generations from now on as an animated GIF
+    Record(PathBuf),
+    /// seed the random pattern, then restart
+    Seed(u64),
+    /// fixed size of the universe, `None`: the size of the terminal
+    Size(Option<Area>),
+}
+impl std::str::FromStr for Command {
+    type Err = Error;
+
+    /// eg. `rule B36/S23`, `goto 1000`, `size 200x100`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (name, arg) = s.split_once(' ').unwrap_or((s, ""));
+        let arg = arg.trim();
+        let Some(&(name, usage)) = COMMANDS.iter().find(|(n, _)| *n == name) else {
+            return Err(Error::Invalid {
+                what: "command",
+                value: name.to_string(),
+            });
+        };
+        if arg.is_empty() {
+            return Err(Error::Invalid {
+                what: "command",
+                value: format!("{name}: missing {usage}"),
+            });
+        }
+        let invalid = |what| Error::Invalid {
+            what,
+            value: arg.to_string(),
+        };
+        let command = match name {
+            "rule" => Command::Rule(arg.parse()?),
+            "goto" => Command::Goto(arg.parse().map_err(|_| invalid("generation"))?),
+            "load" => Command::Load(load::expand_home(arg)),
+            "save" => Command::Save(load::expand_home(arg)),
+            "bookmarks" => Command::SaveBookmarks(load::expand_home(arg)),
+            "record" => Command::Record(load::expand_home(arg)),
+            "seed" => Command::Seed(arg.parse().map_err(|_| invalid("seed"))?),
+            "size" if arg == "auto" => Command::Size(None),
+            "size" => Command::Size(Some(arg.parse()?)),
+            _ => unreachable!("{name} is in COMMANDS"),
+        };
+        Ok(command)
+    }
+}
+
+/// `:` line in the footer for typing commands, with completion and history
+#[derive(Debug, Default)]
+pub struct CommandLine {
+    /// whether a command is being typed
+    pub active: bool,
+    pub input: String,
+    /// possible completions of the last word, after an ambiguous completion
+    pub hints: Vec<String>,
+    /// entered commands, oldest first
+    history: Vec<String>,
+    /// index of the command recalled from `history`, if any
+    recalled: Option<usize>,
+    /// what was typed before going through the history
+    draft: String,
+}
+impl CommandLine {
+    /// Start typing a new command
+    pub fn open(&mut self) {
+        self.active = true;
+        self.input.clear();
+        self.hints.clear();
+        self.recalled = None;
+    }
+    pub fn close(&mut self) {
+        self.active = false;
+    }
+    pub fn push(&mut self, ch: char) {
+        self.input.push(ch);
+        self.hints.clear();
+    }
+    /// Delete the last character, closing if there's none
+    pub fn pop(&mut self) {
+        if self.input.pop().is_none() {
+            self.close();
+        }
+        self.hints.clear();
+    }
+    /// Finish typing, remembering the command.
+    /// Returns the command typed.
+    pub fn enter(&mut self) -> String {
+        self.close();
+        let input = std::mem::take(&mut self.input);
+        if !input.trim().is_empty() && self.history.last() != Some(&input) {
+            self.history.push(input.clone());
+        }
+        input
+    }
+    /// Recall the previous command of the history
+    pub fn prev(&mut self) {
+        let i = match self.recalled {
+            Some(0) => return,
+            Some(i) => i - 1,
+            None if self.history.is_empty() => return,
+            None => {
+                self.draft = self.input.clone();
+                self.history.len() - 1
+            }
+        };
+        self.recalled = Some(i);
+        self.input = self.history[i].clone();
+    }
+    /// Recall the next command of the history, or what was typed before
+    pub fn next(&mut self) {
+        let Some(i) = self.recalled else {
+            return;
+        };
+        if i + 1 < self.history.len() {
+            self.recalled = Some(i + 1);
+            self.input = self.history[i + 1].clone();
+        } else {
+            self.recalled = None;
+            self.input = std::mem::take(&mut self.draft);
+        }
+    }
+    /// Complete the last word as far as it's unambiguous, listing the options in `hints` if there are more
+    pub fn complete(&mut self) {
+        let (start, options) = completions(&self.input);
+        let word = &self.input[start..];
+        // hints without the directory typed
+        let dir = word.rfind('/').map_or(0, |i| i + 1);
+        let Some(first) = options.first() else {
+            self.hints.clear();
+            return;
+        };
+        let common = options.iter().fold(first.as_str(), |common, option| {
+            let len = common
+                .char_indices()
+                .zip(option.chars())
+                .find(|&((_, a), b)| a != b)
+                .map_or(common.len().min(option.len()), |((i, _), _)| i);
+            &common[..len]
+        });
+        let hints = options.iter().map(|o| o[dir..].to_string()).collect();
+        if common.len() > word.len() {
+            let common = common.to_string();
+            self.input.truncate(start);
+            self.input += &common;
+        }
+        if let [only] = options.as_slice() {
+            // a command or a file: on to the next word
+            if !only.ends_with('/') && start == 0 {
+                self.input.push(' ');
+            }
+            self.hints.clear();
+        } else {
+            self.hints = hints;
+        }
+    }
+}
+
+/// Where the last word of `input` starts and what it can be completed to
+fn completions(input: &str) -> (usize, Vec<String>) {
+    let Some((name, arg)) = input.split_once(' ') else {
+        let commands = COMMANDS
+            .iter()
+            .map(|(name, _)| *name)
+            .filter(|name| name.starts_with(input))
+            .map(String::from)
+            .collect();
+        return (0, commands);
+    };
+    let start = name.len() + 1;
+    let options = match name {
+        "load" => paths(arg, true),
+        "save" | "bookmarks" | "record" => paths(arg, false),
+        "size" if "auto".starts_with(arg) => vec!["auto".into()],
+        _ => vec![],
+    };
+    (start, options)
+}
+
+/// Paths starting with `prefix`, directories ending in `/`.
+/// `patterns`: only directories and pattern files
+fn paths(prefix: &str, patterns: bool) -> Vec<String> {
+    let (dir, name) = match prefix.rfind('/') {
+        Some(i) => prefix.split_at(i + 1),
+        None => ("", prefix),
+    };
+    let path = match dir {
+        "" => Path::new(".").to_path_buf(),
+        dir => load::expand_home(dir),
+    };
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return vec![];
+    };
+    let mut paths = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let file_name = entry.file_name().to_string_lossy().into_owned();
+            // hidden ones only when asked for
+            if !file_name.starts_with(name)
+                || (file_name.starts_with('.') && !name.starts_with('.'))
+            {
+                return None;
+            }
+            let is_dir = entry.path().is_dir();
+            if patterns && !is_dir && !load::is_pattern_file(&entry.path()) {
+                return None;
+            }
+            let slash = if is_dir { "/" } else { "" };
+            Some(format!("{dir}{file_name}{slash}"))
+        })
+        .collect::<Vec<_>>();
+    paths.sort();
+    paths
+}
@@ -0,0 +1,208 @@
+use super::{
+    keys::{Action, Key, Keymap, Mode},
+    load, Renderer, Rule, Theme, Topology,
+};
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+use toml::{Table, Value};
+
+/// Environment variable overriding the default config file path
+pub const CONFIG_VAR: &str = "CGOL_TUI_CONFIG";
+
+/// The default config file: `$CGOL_TUI_CONFIG` if set,
+/// otherwise `$XDG_CONFIG_HOME/cgol-tui/config.toml`, falling back to `~/.config/cgol-tui/config.toml`
+pub fn default_path() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os(CONFIG_VAR).filter(|p| !p.is_empty()) {
+        return Some(PathBuf::from(path));
+    }
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|p| !p.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+    Some(config_home.join("cgol-tui").join("config.toml"))
+}
+
+/// Settings read from the config file, those not set are left to the command line or the defaults
+///
+/// ```toml
+/// rule = "B36/S23"
+/// speed = 10
+/// renderer = "halfblock"
+/// theme = "light"
+/// topology = "plane"
+/// library = ["~/patterns"]
+/// pattern = "glider"
+///
+/// [keys.normal]
+/// quit = ["q", "ctrl-c"]
+/// faster = "+"
+/// slower = "-"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Config {
+    pub rule: Option<Rule>,
+    /// generations per second
+    pub speed: Option<f64>,
+    pub renderer: Option<Renderer>,
+    pub theme: Option<Theme>,
+    pub topology: Option<Topology>,
+    /// pattern directories loaded besides the default library,
+    /// relative to the directory of the config file
+    pub library: Vec<PathBuf>,
+    /// name of the pattern to start with
+    pub pattern: Option<String>,
+    /// the default keys, remapped by the `[keys.<mode>]` tables
+    pub keymap: Keymap,
+}
+impl Config {
+    /// Reads the config file at `path`
+    ///
+    /// # Errors
+    ///
+    /// the file can't be read or has invalid settings
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let text = std::fs::read_to_string(path).map_err(|e| ConfigError::new(path, None, e))?;
+        Self::parse(path, &text)
+    }
+    /// Parses the config file at `path` of contents `text`
+    ///
+    /// # Errors
+    ///
+    /// malformed toml or invalid settings
+    pub fn parse(path: &Path, text: &str) -> Result<Self, ConfigError> {
+        let table: Table = text
+            .parse()
+            .map_err(|e: toml::de::Error| ConfigError::new(path, None, e))?;
+        let mut config = Self::default();
+        for (name, value) in &table {
+            let setting = Some(name.as_str());
+            match (name.as_str(), value) {
+                ("rule", _) => config.rule = Some(parse(path, name, value)?),
+                ("speed", Value::Integer(speed)) if *speed > 0 => {
+                    config.speed = Some(*speed as f64)
+                }
+                ("speed", Value::Float(speed)) if *speed > 0. => config.speed = Some(*speed),
+                ("speed", _) => {
+                    return Err(ConfigError::new(
+                        path,
+                        setting,
+                        "should be a positive number",
+                    ))
+                }
+                ("renderer", _) => config.renderer = Some(parse(path, name, value)?),
+                ("theme", _) => config.theme = Some(parse(path, name, value)?),
+                ("topology", _) => config.topology = Some(parse(path, name, value)?),
+                ("library", Value::Array(dirs)) => {
+                    let dir = path.parent().unwrap_or(Path::new(""));
+                    config.library = dirs
+                        .iter()
+                        .map(|p| p.as_str().map(|p| dir.join(load::expand_home(p))))
+                        .collect::<Option<_>>()
+                        .ok_or_else(|| {
+                            ConfigError::new(path, setting, "paths should be strings")
+                        })?;
+                }
+                ("library", _) => {
+                    return Err(ConfigError::new(path, setting, "should be a list of paths"))
+                }
+                ("pattern", Value::String(pattern)) => config.pattern = Some(pattern.clone()),
+                ("pattern", _) => {
+                    return Err(ConfigError::new(path, setting, "should be a pattern name"))
+                }
+                ("keys", Value::Table(keys)) => config.keymap = keymap(path, keys)?,
+                ("keys", _) => return Err(ConfigError::new(path, Some(name), "should be a table")),
+                _ => return Err(ConfigError::new(path, Some(name), "unknown setting")),
+            }
+        }
+        Ok(config)
+    }
+}
+
+/// `value` of `setting` parsed from a string
+fn parse<T>(path: &Path, setting: &str, value: &Value) -> Result<T, ConfigError>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    let value = value
+        .as_str()
+        .ok_or_else(|| ConfigError::new(path, Some(setting), "should be a string"))?;
+    value
+        .parse()
+        .map_err(|e| ConfigError::new(path, Some(setting), e))
+}
+
+/// The default keymap with the actions of each `[keys.<mode>]` table bound to the keys given
+fn keymap(path: &Path, tables: &Table) -> Result<Keymap, ConfigError> {
+    let mut keymap = Keymap::default();
+    for (mode, table) in tables {
+        let setting = format!("keys.{mode}");
+        let err = |setting: &str, reason: &dyn std::fmt::Display| {
+            ConfigError::new(path, Some(setting), reason)
+        };
+        let mode: Mode = mode.parse().map_err(|e| err(&setting, &e))?;
+        let Value::Table(table) = table else {
+            return Err(err(&setting, &"should be a table"));
+        };
+        // keys bound in this table so far, to catch conflicts
+        let mut taken: Vec<(Key, Action)> = vec![];
+        for (action, value) in table {
+            let setting = format!("{setting}.{action}");
+            let action: Action = action.parse().map_err(|e| err(&setting, &e))?;
+            let names = match value {
+                Value::String(key) => vec![key.as_str()],
+                Value::Array(keys) => keys
+                    .iter()
+                    .map(Value::as_str)
+                    .collect::<Option<_>>()
+                    .ok_or_else(|| err(&setting, &"keys should be strings"))?,
+                _ => return Err(err(&setting, &"should be a key or a list of keys")),
+            };
+            let keys = names
+                .into_iter()
+                .map(str::parse)
+                .collect::<Result<Vec<Key>, _>>()
+                .map_err(|e| err(&setting, &e))?;
+            for &key in &keys {
+                if let Some((_, other)) = taken.iter().find(|&&(k, _)| k == key) {
+                    return Err(err(&setting, &format!("{key} is bound to {other} as well")));
+                }
+                taken.push((key, action));
+            }
+            keymap
+                .bind(mode, action, &keys)
+                .map_err(|_| err(&setting, &format!("not available in {mode} mode")))?;
+        }
+    }
+    Ok(keymap)
+}
+
+/// Something wrong with the config file
+#[derive(Debug)]
+pub struct ConfigError {
+    pub path: PathBuf,
+    /// the setting at fault, eg. `keys.normal.quit`
+    pub setting: Option<String>,
+    pub reason: String,
+}
+impl ConfigError {
+    pub fn new(path: &Path, setting: Option<&str>, reason: impl std::fmt::Display) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            setting: setting.map(String::from),
+            reason: reason.to_string(),
+        }
+    }
+}
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: ", self.path.display())?;
+        if let Some(setting) = &self.setting {
+            write!(f, "{setting}: ")?;
+        }
+        write!(f, "{}", self.reason)
+    }
+}
+impl std::error::Error for ConfigError {}
@@ -0,0 +1,110 @@
+use super::{Area, Cell, Orientation, Universe};
+use ratatui::style::Color;
+
+/// Editing the board while paused: a cursor, a rectangular selection and pasting
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Editor {
+    /// (row, col) in the universe
+    pub cursor: (u16, u16),
+    /// corner of the selection opposite to the cursor, if selecting
+    pub anchor: Option<(u16, u16)>,
+    /// pattern being placed with its top left corner at the cursor
+    pub pasting: Option<Paste>,
+}
+
+/// A pattern being placed onto the board
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Paste {
+    pub figur: Universe,
+    pub orientation: Orientation,
+    /// only live cells are placed, keeping the board under dead ones,
+    /// and it can be placed again and again
+    pub stamp: bool,
+}
+impl Paste {
+    pub fn new(figur: Universe, stamp: bool) -> Self {
+        Self {
+            figur,
+            orientation: Orientation::default(),
+            stamp,
+        }
+    }
+}
+impl Editor {
+    pub fn new(cursor: (u16, u16)) -> Self {
+        Self {
+            cursor,
+            ..Default::default()
+        }
+    }
+    /// Top left corner and size of the selection, if selecting
+    pub fn selection(&self) -> Option<((u16, u16), Area)> {
+        let (row, col) = self.cursor;
+        let (a_row, a_col) = self.anchor?;
+        let at = (row.min(a_row), col.min(a_col));
+        let area = Area::new(col.abs_diff(a_col) + 1, row.abs_diff(a_row) + 1);
+        Some((at, area))
+    }
+    /// Start selecting at the cursor, or stop if already selecting
+    pub fn toggle_selection(&mut self) {
+        self.anchor = match self.anchor {
+            Some(_) => None,
+            None => Some(self.cursor),
+        };
+    }
+    /// Move the cursor by `rows` and `cols`, staying inside `bounds`
+    pub fn move_cursor(&mut self, rows: i32, cols: i32, bounds: Area) {
+        let (row, col) = self.cursor;
+        let max_row = i32::from(bounds.height.saturating_sub(1));
+        let max_col = i32::from(bounds.width.saturating_sub(1));
+        self.cursor = (
+            (i32::from(row) + rows).clamp(0, max_row) as u16,
+            (i32::from(col) + cols).clamp(0, max_col) as u16,
+        );
+        if let Some((a_row, a_col)) = self.anchor {
+            self.anchor = Some((a_row.min(max_row as u16), a_col.min(max_col as u16)));
+        }
+    }
+    /// The pattern being pasted, oriented
+    pub fn preview(&self) -> Option<Universe> {
+        self.pasting
+            .as_ref()
+            .map(|paste| paste.orientation.apply(&paste.figur))
+    }
+    /// Change the orientation of the pattern being pasted
+    pub fn orient(&mut self, f: impl FnOnce(Orientation) -> Orientation) {
+        if let Some(paste) = self.pasting.as_mut() {
+            paste.orientation = f(paste.orientation);
+        }
+    }
+
+    /// Cells drawn over the universe: (row, col) and colour.
+    /// The outline of the selection, then the pattern being pasted, then the cursor
+    pub fn overlay(&self) -> Vec<((u16, u16), Color)> {
+        let mut cells = vec![];
+        if let Some(((row, col), area)) = self.selection() {
+            let (bottom, right) = (row + area.height - 1, col + area.width - 1);
+            for c in col..=right {
+                cells.push(((row, c), Color::Yellow));
+                cells.push(((bottom, c), Color::Yellow));
+            }
+            for r in row..=bottom {
+                cells.push(((r, col), Color::Yellow));
+                cells.push(((r, right), Color::Yellow));
+            }
+        }
+        if let Some(preview) = self.preview() {
+            let (row, col) = self.cursor;
+            for r in 0..preview.height() {
+                for c in 0..preview.width() {
+                    if preview[(r, c)] == Cell::Alive {
+                        let at = (row.saturating_add(r), col.saturating_add(c));
+                        cells.push((at, Color::LightGreen));
+                    }
+                }
+            }
+        }
+        cells.push((self.cursor, Color::LightRed));
+        cells
+    }
+}
@@ -0,0 +1,75 @@
+use super::Area;
+use std::io;
+
+/// Everything that can go wrong while creating a `Universe` or its settings
+#[derive(Debug)]
+pub enum Error {
+    /// not a valid `Cell`
+    InvalidCell(char),
+    /// malformed pattern
+    Parse(ParseError),
+    /// a figure of size `figure` doesn't fit into `area`
+    TooBig {
+        figure: Area,
+        area: Area,
+    },
+    Io(io::Error),
+    /// `value` isn't a valid `what`, eg. rule
+    Invalid {
+        what: &'static str,
+        value: String,
+    },
+}
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::InvalidCell(ch) => write!(
+                f,
+                "{ch:?} is an invalid character, should be either '.' or 'O'"
+            ),
+            Error::Parse(e) => write!(f, "{e}"),
+            Error::TooBig { figure, area } => write!(
+                f,
+                "pattern of size {}×{} doesn't fit into {}×{}",
+                figure.width, figure.height, area.width, area.height
+            ),
+            Error::Io(e) => write!(f, "{e}"),
+            Error::Invalid { what, value } => write!(f, "invalid {what}: {value:?}"),
+        }
+    }
+}
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Parse(e) => Some(e),
+            Error::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+impl From<ParseError> for Error {
+    fn from(e: ParseError) -> Self {
+        Self::Parse(e)
+    }
+}
+
+/// Failure to parse a pattern: what went wrong and where
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based
+    pub line: usize,
+    /// 1-based, in characters
+    pub col: usize,
+    pub reason: String,
+}
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.col, self.reason)
+    }
+}
+impl std::error::Error for ParseError {}
@@ -0,0 +1,381 @@
+use super::{Area, Cell, Error, ParseError, Rule, Universe};
+use std::str::FromStr;
+
+/// Maximum length of a line in RLE
+const RLE_LINE_LEN: usize = 70;
+
+/// Supported pattern formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    /// plaintext, `.cells`
+    #[default]
+    Cells,
+    /// run length encoded, `.rle`
+    Rle,
+    /// list of live cell coordinates, `.lif`
+    Life106,
+    /// box drawing characters, as `Universe` is displayed, output only
+    Box,
+}
+impl std::str::FromStr for Format {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "cells" | "plaintext" => Ok(Self::Cells),
+            "rle" => Ok(Self::Rle),
+            "life106" | "lif" => Ok(Self::Life106),
+            "box" => Ok(Self::Box),
+            _ => Err(Error::Invalid {
+                what: "format",
+                value: s.to_string(),
+            }),
+        }
+    }
+}
+impl Format {
+    /// Guess the format from the extension of `path`
+    pub fn from_path(path: &std::path::Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_lowercase().as_str() {
+            "cells" => Some(Self::Cells),
+            "rle" => Some(Self::Rle),
+            "lif" | "life" => Some(Self::Life106),
+            _ => None,
+        }
+    }
+    /// Guess the format of `s` from it's first meaningful line
+    pub fn detect(s: &str) -> Self {
+        for line in s.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            if line.starts_with("#Life 1.06") {
+                return Self::Life106;
+            }
+            match line.chars().next() {
+                // RLE comment
+                Some('#') => continue,
+                Some('x') => return Self::Rle,
+                _ => return Self::Cells,
+            }
+        }
+        Self::Cells
+    }
+    /// Parse `s` as this format
+    ///
+    /// # Errors
+    ///
+    /// `s` is malformed, or this format can't be parsed
+    pub fn parse(self, s: &str) -> Result<Universe, Error> {
+        match self {
+            Format::Cells => Universe::from_str(s),
+            Format::Rle => from_rle(s),
+            Format::Life106 => from_life106(s),
+            Format::Box => Err(Error::Invalid {
+                what: "input format",
+                value: "box".into(),
+            }),
+        }
+    }
+    /// `univ` written in this format
+    pub fn write(self, univ: &Universe) -> String {
+        match self {
+            Format::Cells => to_cells(univ),
+            Format::Rle => to_rle(univ),
+            Format::Life106 => to_life106(univ),
+            Format::Box => univ.to_string().replace('\r', ""),
+        }
+    }
+    /// `univ` written in this format, with `comments` after the first line.
+    /// Box drawing has no comments.
+    pub fn write_commented(self, univ: &Universe, comments: &[String]) -> String {
+        let s = self.write(univ);
+        let prefix = match self {
+            Format::Cells => "!",
+            Format::Rle => "#C ",
+            Format::Life106 => "#D ",
+            Format::Box => return s,
+        };
+        let (first, rest) = s.split_once('\n').unwrap_or((&s, ""));
+        let comments = comments
+            .iter()
+            .map(|comment| format!("{prefix}{comment}\n"))
+            .collect::<String>();
+        format!("{first}\n{comments}{rest}")
+    }
+}
+
+/// Parse `s`, guessing its format
+///
+/// # Errors
+///
+/// `s` is malformed
+pub fn parse(s: &str) -> Result<Universe, Error> {
+    Format::detect(s).parse(s)
+}
+
+/// ```text
+/// !Name: Glider
+/// ..O
+/// O.O
+/// .OO
+/// ```
+pub fn to_cells(univ: &Universe) -> String {
+    let mut s = format!("!Name: {}\n", univ.name());
+    for line in univ.cells.chunks(univ.width().max(1) as usize) {
+        for &cell in line {
+            s.push(if cell == Cell::Alive { 'O' } else { '.' });
+        }
+        s.push('\n');
+    }
+    s
+}
+
+/// ```text
+/// #N Glider
+/// x = 3, y = 3, rule = B3/S23
+/// 2bo$obo$b2o!
+/// ```
+pub fn to_rle(univ: &Universe) -> String {
+    // (run length, tag) pairs
+    let mut runs: Vec<(usize, char)> = vec![];
+    let mut push = |n: usize, tag: char| match runs.last_mut() {
+        Some((len, last)) if *last == tag => *len += n,
+        _ => runs.push((n, tag)),
+    };
+    for line in univ.cells.chunks(univ.width().max(1) as usize) {
+        // trailing dead cells aren't written
+        let end = line
+            .iter()
+            .rposition(|&c| c == Cell::Alive)
+            .map_or(0, |i| i + 1);
+        for &cell in &line[..end] {
+            push(1, if cell == Cell::Alive { 'o' } else { 'b' });
+        }
+        push(1, '$');
+    }
+    // trailing empty lines aren't written either
+    while runs.last().is_some_and(|(_, tag)| *tag == '$') {
+        runs.pop();
+    }
+
+    let mut s = format!(
+        "#N {}\nx = {}, y = {}, rule = {}\n",
+        univ.name(),
+        univ.width(),
+        univ.height(),
+        univ.rule
+    );
+    let mut line = String::new();
+    for (len, tag) in runs {
+        let item = if len == 1 {
+            tag.to_string()
+        } else {
+            format!("{len}{tag}")
+        };
+        if line.len() + item.len() > RLE_LINE_LEN {
+            s.push_str(&line);
+            s.push('\n');
+            line.clear();
+        }
+        line.push_str(&item);
+    }
+    s.push_str(&line);
+    s.push_str("!\n");
+    s
+}
+
+/// ```text
+/// #Life 1.06
+/// 2 0
+/// 0 1
+/// 2 1
+/// 1 2
+/// 2 2
+/// ```
+pub fn to_life106(univ: &Universe) -> String {
+    let mut s = String::from("#Life 1.06\n");
+    for row in 0..univ.height() {
+        for col in 0..univ.width() {
+            if univ[(row, col)] == Cell::Alive {
+                s.push_str(&format!("{col} {row}\n"));
+            }
+        }
+    }
+    s
+}
+
+/// Error at `line`:`col` because of `reason`
+fn parse_error(line: usize, col: usize, reason: impl ToString) -> Error {
+    Error::Parse(ParseError {
+        line,
+        col,
+        reason: reason.to_string(),
+    })
+}
+
+/// Universe of the smallest area fitting all the `alive` (row, col) coordinates
+fn from_coords(alive: &[(i64, i64)]) -> Result<Universe, Error> {
+    if alive.is_empty() {
+        return Ok(Universe::new(Area::default(), vec![], ""));
+    }
+    let (min_row, max_row) = alive
+        .iter()
+        .map(|c| c.0)
+        .fold((i64::MAX, i64::MIN), |(min, max), r| {
+            (min.min(r), max.max(r))
+        });
+    let (min_col, max_col) = alive
+        .iter()
+        .map(|c| c.1)
+        .fold((i64::MAX, i64::MIN), |(min, max), c| {
+            (min.min(c), max.max(c))
+        });
+    let span = |min: i64, max: i64| i128::from(max) - i128::from(min) + 1;
+    let too_big = || Error::Invalid {
+        what: "pattern size",
+        value: format!("{}×{}", span(min_col, max_col), span(min_row, max_row)),
+    };
+    let width = u16::try_from(span(min_col, max_col)).map_err(|_| too_big())?;
+    let height = u16::try_from(span(min_row, max_row)).map_err(|_| too_big())?;
+    let area = Area::new(width, height);
+    let mut univ = Universe::new(area, vec![Cell::Dead; area.len()], "");
+    for (row, col) in alive {
+        univ[((row - min_row) as usize, (col - min_col) as usize)] = Cell::Alive;
+    }
+    Ok(univ)
+}
+
+/// Parse Life 1.06: `#Life 1.06`, then a line of `<x> <y>` for each live cell
+///
+/// # Errors
+///
+/// not a pair of integers on a line
+pub fn from_life106(s: &str) -> Result<Universe, Error> {
+    let mut alive = vec![];
+    for (i, line) in s.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let col = line.chars().take_while(|c| c.is_whitespace()).count() + 1;
+        let mut coords = trimmed.split_whitespace().map(i64::from_str);
+        match (coords.next(), coords.next(), coords.next()) {
+            (Some(Ok(x)), Some(Ok(y)), None) => alive.push((y, x)),
+            _ => {
+                return Err(parse_error(
+                    i + 1,
+                    col,
+                    "should be a pair of integers: <x> <y>",
+                ))
+            }
+        }
+    }
+    from_coords(&alive)
+}
+
+/// Parse run length encoded: `#` comments, `x = <width>, y = <height>, rule = <rule>` header,
+/// then `<count><tag>` items: `b` dead, `o` alive, `$` end of line, `!` end of pattern
+///
+/// # Errors
+///
+/// malformed header or pattern, or pattern not fitting into the header's size
+pub fn from_rle(s: &str) -> Result<Universe, Error> {
+    let mut name = String::new();
+    let mut rule = Rule::default();
+    // built once the header is read
+    let mut univ: Option<Universe> = None;
+    let (mut row, mut col) = (0_i64, 0_i64);
+    let mut count: Option<i64> = None;
+
+    'lines: for (i, line) in s.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let Some(univ) = univ.as_mut() else {
+            if let Some(comment) = trimmed.strip_prefix('#') {
+                let mut comment = comment.chars();
+                match comment.next() {
+                    Some('N') => name = comment.as_str().trim().to_string(),
+                    Some('r') => {
+                        rule = Rule::from_str(comment.as_str())
+                            .map_err(|e| parse_error(i + 1, 1, e))?;
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+            let (area, header_rule) = parse_rle_header(trimmed, i + 1)?;
+            let rule = header_rule.unwrap_or(rule);
+            univ = Some(Universe::new(area, vec![Cell::Dead; area.len()], &name).with_rule(rule));
+            continue;
+        };
+
+        for (j, ch) in line.chars().enumerate() {
+            if let Some(digit) = ch.to_digit(10) {
+                let n = count.unwrap_or(0).saturating_mul(10);
+                count = Some(n.saturating_add(digit.into()));
+                continue;
+            }
+            if ch.is_whitespace() {
+                continue;
+            }
+            let n = count.take().unwrap_or(1);
+            match ch {
+                'b' | '.' => col = col.saturating_add(n),
+                '$' => (row, col) = (row.saturating_add(n), 0),
+                '!' => break 'lines,
+                // any other state counts as alive
+                ch if ch.is_ascii_alphabetic() => {
+                    let end = col.saturating_add(n);
+                    // checked before filling, the run may be huge
+                    let area = univ.area;
+                    if row >= i64::from(area.height) || end > i64::from(area.width) {
+                        let size = |n: i64| u16::try_from(n).unwrap_or(u16::MAX);
+                        return Err(Error::TooBig {
+                            figure: Area::new(size(end), size(row.saturating_add(1))),
+                            area,
+                        });
+                    }
+                    for c in col..end {
+                        univ[(row as usize, c as usize)] = Cell::Alive;
+                    }
+                    col = end;
+                }
+                ch => {
+                    return Err(parse_error(
+                        i + 1,
+                        j + 1,
+                        format!("invalid character: {ch:?}"),
+                    ))
+                }
+            }
+        }
+    }
+
+    univ.ok_or_else(|| parse_error(1, 1, "missing header: x = <width>, y = <height>"))
+}
+
+/// `x = <width>, y = <height>, rule = <rule>`, rule being optional
+fn parse_rle_header(line: &str, line_no: usize) -> Result<(Area, Option<Rule>), Error> {
+    let (mut width, mut height, mut rule) = (None, None, None);
+    for item in line.split(',') {
+        let Some((key, value)) = item.split_once('=') else {
+            return Err(parse_error(
+                line_no,
+                1,
+                format!("invalid header item: {item:?}"),
+            ));
+        };
+        let value = value.trim();
+        let invalid = |what| parse_error(line_no, 1, format!("invalid {what}: {value:?}"));
+        match key.trim() {
+            "x" => width = Some(value.parse().map_err(|_| invalid("width"))?),
+            "y" => height = Some(value.parse().map_err(|_| invalid("height"))?),
+            "rule" => rule = Some(Rule::from_str(value).map_err(|_| invalid("rule"))?),
+            _ => {}
+        }
+    }
+    match (width, height) {
+        (Some(width), Some(height)) => Ok((Area::new(width, height), rule)),
+        _ => Err(parse_error(line_no, 1, "header should have both x and y")),
+    }
+}
@@ -0,0 +1,76 @@
+use super::{record::rgb, Universe};
+use ratatui::style::Color;
+use std::collections::VecDeque;
+
+/// How often each cell changed state over the last `generations` generations
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HeatMap {
+    generations: usize,
+    /// changes per cell within the window
+    counts: Vec<u16>,
+    /// cells changed by each generation in the window, oldest first
+    window: VecDeque<Vec<usize>>,
+}
+impl HeatMap {
+    pub fn new(generations: usize) -> Self {
+        Self {
+            generations: generations.max(1),
+            ..Default::default()
+        }
+    }
+    /// Count the cells changed by the last generation of `univ`
+    pub fn record(&mut self, univ: &Universe) {
+        if self.counts.len() != univ.cells.len() {
+            self.counts = vec![0; univ.cells.len()];
+            self.window.clear();
+        }
+        let changed = univ.changed().collect::<Vec<_>>();
+        for &i in &changed {
+            self.counts[i] = self.counts[i].saturating_add(1);
+        }
+        self.window.push_back(changed);
+        if self.window.len() > self.generations {
+            for i in self.window.pop_front().unwrap_or_default() {
+                self.counts[i] = self.counts[i].saturating_sub(1);
+            }
+        }
+    }
+    /// Start accumulating again
+    pub fn reset(&mut self) {
+        self.counts.clear();
+        self.window.clear();
+    }
+    /// Changes of the cell at index `i` within the window, relative to the most possible: 0..=1
+    pub fn heat(&self, i: usize) -> f64 {
+        let count = self.counts.get(i).copied().unwrap_or(0);
+        f64::from(count) / self.generations as f64
+    }
+    /// Colour of `heat` on a gradient from blue through red to yellow, `None` if cold
+    pub fn color(heat: f64) -> Option<Color> {
+        if heat <= 0. {
+            return None;
+        }
+        let (blue, red, yellow) = ([40, 40, 200], [220, 40, 40], [255, 230, 60]);
+        // rare changes stay visible
+        let heat = heat.sqrt().min(1.);
+        Some(if heat < 0.5 {
+            lerp(blue, red, heat * 2.)
+        } else {
+            lerp(red, yellow, heat * 2. - 1.)
+        })
+    }
+    /// `color` of a live cell half way to the colour of its `heat`, unchanged if cold.
+    /// The colour of the heat alone if `color` depends on the terminal.
+    pub fn tint(color: Color, heat: f64) -> Color {
+        match (rgb(color), Self::color(heat)) {
+            (Some(color), Some(Color::Rgb(r, g, b))) => lerp(color, [r, g, b], 0.5),
+            (_, hot) => hot.unwrap_or(color),
+        }
+    }
+}
+
+/// `t` of the way from `from` to `to`: 0..=1
+fn lerp(from: [u8; 3], to: [u8; 3], t: f64) -> Color {
+    let mix = |i: usize| (f64::from(from[i]) + (f64::from(to[i]) - f64::from(from[i])) * t) as u8;
+    Color::Rgb(mix(0), mix(1), mix(2))
+}
@@ -0,0 +1,94 @@
+use super::{Cell, Universe};
+use std::collections::VecDeque;
+
+/// Cells changed by one edit: (index, before, after)
+type Change = Vec<(usize, Cell, Cell)>;
+
+/// Edits of the board that can be undone and redone, independently of generations run since.
+/// Only the changed cells are kept, the oldest edits are forgotten once `budget` bytes are used.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct History {
+    undo: VecDeque<Change>,
+    redo: Vec<Change>,
+    /// bytes available for changes
+    budget: usize,
+}
+impl History {
+    pub fn new(budget: usize) -> Self {
+        Self {
+            budget,
+            ..Default::default()
+        }
+    }
+    /// Remember the edit from `before` to `after`, forgetting what was undone
+    pub fn record(&mut self, before: &Universe, after: &Universe) {
+        if before.area != after.area {
+            // indices don't match anymore
+            self.clear();
+            return;
+        }
+        let change = before
+            .cells
+            .iter()
+            .zip(&after.cells)
+            .enumerate()
+            .filter(|(_, (b, a))| b != a)
+            .map(|(i, (&b, &a))| (i, b, a))
+            .collect::<Change>();
+        if change.is_empty() {
+            return;
+        }
+        self.redo.clear();
+        self.undo.push_back(change);
+        while self.size() > self.budget && self.undo.pop_front().is_some() {}
+    }
+    /// Revert the last edit in `univ`
+    ///
+    /// # Returns
+    ///
+    /// whether there was anything to undo
+    pub fn undo(&mut self, univ: &mut Universe) -> bool {
+        let Some(change) = self.undo.pop_back() else {
+            return false;
+        };
+        for &(i, before, _) in &change {
+            univ.cells[i] = before;
+        }
+        self.redo.push(change);
+        true
+    }
+    /// Apply the last undone edit to `univ` again
+    ///
+    /// # Returns
+    ///
+    /// whether there was anything to redo
+    pub fn redo(&mut self, univ: &mut Universe) -> bool {
+        let Some(change) = self.redo.pop() else {
+            return false;
+        };
+        for &(i, _, after) in &change {
+            univ.cells[i] = after;
+        }
+        self.undo.push_back(change);
+        true
+    }
+    /// Whether there's no edit to undo or redo
+    pub fn is_empty(&self) -> bool {
+        self.undo.is_empty() && self.redo.is_empty()
+    }
+    /// Forget every edit, eg. when the universe is replaced
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+    /// Bytes used by the changes
+    pub fn size(&self) -> usize {
+        let cells = self
+            .undo
+            .iter()
+            .chain(&self.redo)
+            .map(Vec::len)
+            .sum::<usize>();
+        cells * std::mem::size_of::<(usize, Cell, Cell)>()
+    }
+}
@@ -0,0 +1,663 @@
+use super::Error;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// Which keys are active
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Mode {
+    /// watching the simulation
+    Normal,
+    /// editing the board
+    Edit,
+    /// placing a pattern onto the board
+    Paste,
+    /// pattern browser
+    Browser,
+    /// bookmarks panel
+    Bookmarks,
+}
+impl std::fmt::Display for Mode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Mode::Normal => write!(f, "normal"),
+            Mode::Edit => write!(f, "edit"),
+            Mode::Paste => write!(f, "paste"),
+            Mode::Browser => write!(f, "browser"),
+            Mode::Bookmarks => write!(f, "bookmarks"),
+        }
+    }
+}
+impl std::str::FromStr for Mode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [
+            Mode::Normal,
+            Mode::Edit,
+            Mode::Paste,
+            Mode::Browser,
+            Mode::Bookmarks,
+        ]
+        .into_iter()
+        .find(|mode| mode.to_string() == s)
+        .ok_or_else(|| Error::Invalid {
+            what: "mode",
+            value: s.to_string(),
+        })
+    }
+}
+
+/// What a key does
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Quit,
+    Slower,
+    Faster,
+    PlayPause,
+    Restart,
+    NextPattern,
+    PrevPattern,
+    Browse,
+    Edit,
+    ScrollLeft,
+    ScrollDown,
+    ScrollUp,
+    ScrollRight,
+    RotateRight,
+    RotateLeft,
+    FlipHorizontal,
+    FlipVertical,
+    Transpose,
+    Reset,
+    CopyRle,
+    NextRenderer,
+    NextTheme,
+    ToggleAge,
+    ToggleHeat,
+    ResetHeat,
+    Undo,
+    Redo,
+    Help,
+    Left,
+    Down,
+    Up,
+    Right,
+    FarLeft,
+    FarDown,
+    FarUp,
+    FarRight,
+    ToggleCell,
+    Select,
+    Copy,
+    Cut,
+    Clear,
+    Fill,
+    Paste,
+    Place,
+    Back,
+    Search,
+    SelectNext,
+    SelectPrev,
+    Load,
+    Stamp,
+    CommandLine,
+    Goto,
+    Bookmark,
+    Bookmarks,
+    Delete,
+    SaveBookmarks,
+    Record,
+}
+impl Action {
+    /// Whether it changes the cells of the board, as an edit that can be undone
+    pub fn edits(self) -> bool {
+        matches!(
+            self,
+            Action::ToggleCell | Action::Cut | Action::Clear | Action::Fill | Action::Place
+        )
+    }
+    /// Every action
+    pub const ALL: [Action; 57] = [
+        Action::Quit,
+        Action::Slower,
+        Action::Faster,
+        Action::PlayPause,
+        Action::Restart,
+        Action::NextPattern,
+        Action::PrevPattern,
+        Action::Browse,
+        Action::Edit,
+        Action::ScrollLeft,
+        Action::ScrollDown,
+        Action::ScrollUp,
+        Action::ScrollRight,
+        Action::RotateRight,
+        Action::RotateLeft,
+        Action::FlipHorizontal,
+        Action::FlipVertical,
+        Action::Transpose,
+        Action::Reset,
+        Action::CopyRle,
+        Action::NextRenderer,
+        Action::NextTheme,
+        Action::ToggleAge,
+        Action::ToggleHeat,
+        Action::ResetHeat,
+        Action::Undo,
+        Action::Redo,
+        Action::Help,
+        Action::Left,
+        Action::Down,
+        Action::Up,
+        Action::Right,
+        Action::FarLeft,
+        Action::FarDown,
+        Action::FarUp,
+        Action::FarRight,
+        Action::ToggleCell,
+        Action::Select,
+        Action::Copy,
+        Action::Cut,
+        Action::Clear,
+        Action::Fill,
+        Action::Paste,
+        Action::Place,
+        Action::Back,
+        Action::Search,
+        Action::SelectNext,
+        Action::SelectPrev,
+        Action::Load,
+        Action::Stamp,
+        Action::CommandLine,
+        Action::Goto,
+        Action::Bookmark,
+        Action::Bookmarks,
+        Action::Delete,
+        Action::SaveBookmarks,
+        Action::Record,
+    ];
+
+    /// How it's called in the config file, eg. `play-pause`
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::Slower => "slower",
+            Action::Faster => "faster",
+            Action::PlayPause => "play-pause",
+            Action::Restart => "restart",
+            Action::NextPattern => "next-pattern",
+            Action::PrevPattern => "prev-pattern",
+            Action::Browse => "browse",
+            Action::Edit => "edit",
+            Action::ScrollLeft => "scroll-left",
+            Action::ScrollDown => "scroll-down",
+            Action::ScrollUp => "scroll-up",
+            Action::ScrollRight => "scroll-right",
+            Action::RotateRight => "rotate-right",
+            Action::RotateLeft => "rotate-left",
+            Action::FlipHorizontal => "flip-horizontal",
+            Action::FlipVertical => "flip-vertical",
+            Action::Transpose => "transpose",
+            Action::Reset => "reset",
+            Action::CopyRle => "copy-rle",
+            Action::NextRenderer => "next-renderer",
+            Action::NextTheme => "next-theme",
+            Action::ToggleAge => "toggle-age",
+            Action::ToggleHeat => "toggle-heat",
+            Action::ResetHeat => "reset-heat",
+            Action::Undo => "undo",
+            Action::Redo => "redo",
+            Action::Help => "help",
+            Action::Left => "left",
+            Action::Down => "down",
+            Action::Up => "up",
+            Action::Right => "right",
+            Action::FarLeft => "far-left",
+            Action::FarDown => "far-down",
+            Action::FarUp => "far-up",
+            Action::FarRight => "far-right",
+            Action::ToggleCell => "toggle-cell",
+            Action::Select => "select",
+            Action::Copy => "copy",
+            Action::Cut => "cut",
+            Action::Clear => "clear",
+            Action::Fill => "fill",
+            Action::Paste => "paste",
+            Action::Place => "place",
+            Action::Back => "back",
+            Action::Search => "search",
+            Action::SelectNext => "select-next",
+            Action::SelectPrev => "select-prev",
+            Action::Load => "load",
+            Action::Stamp => "stamp",
+            Action::CommandLine => "command-line",
+            Action::Goto => "goto",
+            Action::Bookmark => "bookmark",
+            Action::Bookmarks => "bookmarks",
+            Action::Delete => "delete",
+            Action::SaveBookmarks => "save-bookmarks",
+            Action::Record => "record",
+        }
+    }
+    /// What it does, shown in the help
+    pub fn help(self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::Slower => "slower",
+            Action::Faster => "faster",
+            Action::PlayPause => "play/pause",
+            Action::Restart => "restart",
+            Action::NextPattern => "next pattern",
+            Action::PrevPattern => "previous pattern",
+            Action::Browse => "browse patterns",
+            Action::Edit => "edit the board, pausing",
+            Action::ScrollLeft => "scroll left",
+            Action::ScrollDown => "scroll down",
+            Action::ScrollUp => "scroll up",
+            Action::ScrollRight => "scroll right",
+            Action::RotateRight => "rotate clockwise",
+            Action::RotateLeft => "rotate counterclockwise",
+            Action::FlipHorizontal => "mirror left to right",
+            Action::FlipVertical => "mirror top to bottom",
+            Action::Transpose => "mirror along the diagonal",
+            Action::Reset => "reset everything",
+            Action::CopyRle => "copy as RLE to the clipboard",
+            Action::NextRenderer => "next renderer",
+            Action::NextTheme => "next colour theme",
+            Action::ToggleAge => "colour cells by age",
+            Action::ToggleHeat => "heat map",
+            Action::ResetHeat => "reset the heat map",
+            Action::Undo => "undo",
+            Action::Redo => "redo",
+            Action::Help => "this help",
+            Action::Left => "cursor left",
+            Action::Down => "cursor down",
+            Action::Up => "cursor up",
+            Action::Right => "cursor right",
+            Action::FarLeft => "cursor far left",
+            Action::FarDown => "cursor far down",
+            Action::FarUp => "cursor far up",
+            Action::FarRight => "cursor far right",
+            Action::ToggleCell => "toggle the cell",
+            Action::Select => "start/stop selecting",
+            Action::Copy => "copy the selection",
+            Action::Cut => "cut the selection",
+            Action::Clear => "clear the selection",
+            Action::Fill => "fill the selection",
+            Action::Paste => "paste",
+            Action::Place => "place",
+            Action::Back => "back",
+            Action::Search => "search",
+            Action::SelectNext => "next entry",
+            Action::SelectPrev => "previous entry",
+            Action::Load => "load",
+            Action::Stamp => "stamp onto the board",
+            Action::CommandLine => "command line",
+            Action::Goto => "go to generation, esc cancels",
+            Action::Bookmark => "bookmark this generation",
+            Action::Bookmarks => "bookmarks",
+            Action::Delete => "delete",
+            Action::SaveBookmarks => "save them to a pattern file",
+            Action::Record => "record a GIF, or stop",
+        }
+    }
+}
+impl std::fmt::Display for Action {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+impl std::str::FromStr for Action {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|action| action.name() == s)
+            .ok_or_else(|| Error::Invalid {
+                what: "action",
+                value: s.to_string(),
+            })
+    }
+}
+
+/// A key with the modifiers that matter: shift is part of the character
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Key {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+impl Key {
+    pub const fn new(code: KeyCode) -> Self {
+        Self {
+            code,
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+    pub const fn ch(ch: char) -> Self {
+        Self::new(KeyCode::Char(ch))
+    }
+    pub const fn ctrl(ch: char) -> Self {
+        Self {
+            code: KeyCode::Char(ch),
+            modifiers: KeyModifiers::CONTROL,
+        }
+    }
+}
+impl From<KeyEvent> for Key {
+    fn from(key: KeyEvent) -> Self {
+        Self {
+            code: key.code,
+            modifiers: key.modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT),
+        }
+    }
+}
+/// Names of the keys that aren't characters
+const NAMED: [(&str, KeyCode); 15] = [
+    ("space", KeyCode::Char(' ')),
+    ("enter", KeyCode::Enter),
+    ("esc", KeyCode::Esc),
+    ("backspace", KeyCode::Backspace),
+    ("tab", KeyCode::Tab),
+    ("delete", KeyCode::Delete),
+    ("insert", KeyCode::Insert),
+    ("left", KeyCode::Left),
+    ("right", KeyCode::Right),
+    ("up", KeyCode::Up),
+    ("down", KeyCode::Down),
+    ("home", KeyCode::Home),
+    ("end", KeyCode::End),
+    ("pageup", KeyCode::PageUp),
+    ("pagedown", KeyCode::PageDown),
+];
+impl std::fmt::Display for Key {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "ctrl-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "alt-")?;
+        }
+        if let Some((name, _)) = NAMED.iter().find(|(_, code)| *code == self.code) {
+            return write!(f, "{name}");
+        }
+        match self.code {
+            KeyCode::Char(ch) => write!(f, "{ch}"),
+            KeyCode::F(n) => write!(f, "f{n}"),
+            code => write!(f, "{code:?}"),
+        }
+    }
+}
+impl std::str::FromStr for Key {
+    type Err = Error;
+
+    /// eg. `q`, `ctrl-r`, `alt-enter`, `f5`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::Invalid {
+            what: "key",
+            value: s.to_string(),
+        };
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = s;
+        // `-` on its own is a key, not a separator
+        while let Some((modifier, key)) = rest.split_once('-').filter(|(_, key)| !key.is_empty()) {
+            modifiers |= match modifier.to_lowercase().as_str() {
+                "ctrl" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                _ => return Err(invalid()),
+            };
+            rest = key;
+        }
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(ch), None) => KeyCode::Char(ch),
+            _ => {
+                let name = rest.to_lowercase();
+                NAMED
+                    .iter()
+                    .find(|(n, _)| *n == name)
+                    .map(|&(_, code)| code)
+                    .or_else(|| {
+                        let n = name.strip_prefix('f')?.parse().ok()?;
+                        (1..=12).contains(&n).then_some(KeyCode::F(n))
+                    })
+                    .ok_or_else(invalid)?
+            }
+        };
+        Ok(Self { code, modifiers })
+    }
+}
+
+use Action as A;
+const fn ch(ch: char) -> Key {
+    Key::ch(ch)
+}
+/// Moving the cursor, the same when editing and when pasting
+const CURSOR: [(&[Key], Action); 8] = [
+    (&[ch('h'), Key::new(KeyCode::Left)], A::Left),
+    (&[ch('j'), Key::new(KeyCode::Down)], A::Down),
+    (&[ch('k'), Key::new(KeyCode::Up)], A::Up),
+    (&[ch('l'), Key::new(KeyCode::Right)], A::Right),
+    (&[ch('H')], A::FarLeft),
+    (&[ch('J')], A::FarDown),
+    (&[ch('K')], A::FarUp),
+    (&[ch('L')], A::FarRight),
+];
+/// Changing the orientation, of the patterns or the one being pasted
+const ORIENT: [(&[Key], Action); 5] = [
+    (&[ch('>')], A::RotateRight),
+    (&[ch('<')], A::RotateLeft),
+    (&[ch('|')], A::FlipHorizontal),
+    (&[ch('_')], A::FlipVertical),
+    (&[ch('t')], A::Transpose),
+];
+/// Undoing edits, in every mode but the browser
+const HISTORY: [(&[Key], Action); 2] = [(&[ch('u')], A::Undo), (&[Key::ctrl('r')], A::Redo)];
+
+const NORMAL: &[(&[Key], Action)] = &[
+    (&[ch('q'), Key::new(KeyCode::Esc)], A::Quit),
+    (&[ch(' '), Key::new(KeyCode::Enter)], A::PlayPause),
+    (&[ch('j'), Key::new(KeyCode::Down)], A::Slower),
+    (&[ch('k'), Key::new(KeyCode::Up)], A::Faster),
+    (&[ch('r')], A::Restart),
+    (&[ch('G')], A::Goto),
+    (
+        &[ch('n'), ch('l'), Key::new(KeyCode::Right)],
+        A::NextPattern,
+    ),
+    (&[ch('p'), ch('h'), Key::new(KeyCode::Left)], A::PrevPattern),
+    (&[ch('b')], A::Browse),
+    (&[ch('e')], A::Edit),
+    (&[ch('s')], A::Bookmark),
+    (&[ch('\'')], A::Bookmarks),
+    (&[ch('w')], A::Record),
+    (&[ch('H')], A::ScrollLeft),
+    (&[ch('J')], A::ScrollDown),
+    (&[ch('K')], A::ScrollUp),
+    (&[ch('L')], A::ScrollRight),
+    ORIENT[0],
+    ORIENT[1],
+    ORIENT[2],
+    ORIENT[3],
+    ORIENT[4],
+    (&[ch('g')], A::NextRenderer),
+    (&[ch('c')], A::NextTheme),
+    (&[ch('a')], A::ToggleAge),
+    (&[ch('m')], A::ToggleHeat),
+    (&[ch('M')], A::ResetHeat),
+    (&[ch('Y')], A::CopyRle),
+    HISTORY[0],
+    HISTORY[1],
+    (&[ch(':')], A::CommandLine),
+    (&[ch('R'), Key::new(KeyCode::Backspace)], A::Reset),
+    (&[ch('?')], A::Help),
+];
+const EDIT: &[(&[Key], Action)] = &[
+    CURSOR[0],
+    CURSOR[1],
+    CURSOR[2],
+    CURSOR[3],
+    CURSOR[4],
+    CURSOR[5],
+    CURSOR[6],
+    CURSOR[7],
+    (&[ch(' '), Key::new(KeyCode::Enter)], A::ToggleCell),
+    (&[ch('v')], A::Select),
+    (&[ch('y')], A::Copy),
+    (&[ch('x')], A::Cut),
+    (&[ch('d')], A::Clear),
+    (&[ch('f')], A::Fill),
+    (&[ch('p')], A::Paste),
+    (&[ch('Y')], A::CopyRle),
+    HISTORY[0],
+    HISTORY[1],
+    (&[ch(':')], A::CommandLine),
+    (&[ch('q'), ch('e'), Key::new(KeyCode::Esc)], A::Back),
+    (&[ch('?')], A::Help),
+];
+const PASTE: &[(&[Key], Action)] = &[
+    CURSOR[0],
+    CURSOR[1],
+    CURSOR[2],
+    CURSOR[3],
+    CURSOR[4],
+    CURSOR[5],
+    CURSOR[6],
+    CURSOR[7],
+    ORIENT[0],
+    ORIENT[1],
+    ORIENT[2],
+    ORIENT[3],
+    ORIENT[4],
+    (&[ch('p'), Key::new(KeyCode::Enter)], A::Place),
+    HISTORY[0],
+    HISTORY[1],
+    (&[ch('q'), Key::new(KeyCode::Esc)], A::Back),
+    (&[ch('?')], A::Help),
+];
+const BROWSER: &[(&[Key], Action)] = &[
+    (&[ch('j'), Key::new(KeyCode::Down)], A::SelectNext),
+    (&[ch('k'), Key::new(KeyCode::Up)], A::SelectPrev),
+    (&[Key::new(KeyCode::Enter)], A::Load),
+    (&[ch('s')], A::Stamp),
+    (&[ch('/')], A::Search),
+    (&[ch('q'), ch('b'), Key::new(KeyCode::Esc)], A::Back),
+    (&[ch('?')], A::Help),
+];
+const BOOKMARKS: &[(&[Key], Action)] = &[
+    (&[ch('j'), Key::new(KeyCode::Down)], A::SelectNext),
+    (&[ch('k'), Key::new(KeyCode::Up)], A::SelectPrev),
+    (&[Key::new(KeyCode::Enter)], A::Load),
+    (&[ch(' ')], A::PlayPause),
+    (&[ch('s')], A::Bookmark),
+    (&[ch('d'), Key::new(KeyCode::Delete)], A::Delete),
+    (&[ch('w')], A::SaveBookmarks),
+    (&[ch('q'), ch('\''), Key::new(KeyCode::Esc)], A::Back),
+    (&[ch('?')], A::Help),
+];
+
+/// Which key does what in which mode
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Keymap {
+    /// in the order shown in the help
+    bindings: Vec<(Mode, Key, Action)>,
+}
+impl Default for Keymap {
+    fn default() -> Self {
+        let modes = [
+            (Mode::Normal, NORMAL),
+            (Mode::Edit, EDIT),
+            (Mode::Paste, PASTE),
+            (Mode::Browser, BROWSER),
+            (Mode::Bookmarks, BOOKMARKS),
+        ];
+        let bindings = modes
+            .into_iter()
+            .flat_map(|(mode, table)| {
+                table.iter().flat_map(move |&(keys, action)| {
+                    keys.iter().map(move |&key| (mode, key, action))
+                })
+            })
+            .collect();
+        Self { bindings }
+    }
+}
+impl Keymap {
+    /// What `key` does in `mode`
+    pub fn action(&self, mode: Mode, key: Key) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|&&(m, k, _)| m == mode && k == key)
+            .map(|&(_, _, action)| action)
+    }
+    /// Binds `action` to exactly `keys` in `mode`, taking them from other actions of `mode`.
+    /// No keys unbinds it.
+    ///
+    /// # Errors
+    ///
+    /// `action` does nothing in `mode`
+    pub fn bind(&mut self, mode: Mode, action: Action, keys: &[Key]) -> Result<(), Error> {
+        if Self::default().help(mode).iter().all(|&(_, a)| a != action) {
+            return Err(Error::Invalid {
+                what: "action",
+                value: format!("{mode}.{action}"),
+            });
+        }
+        let new = keys.iter().map(|&key| (mode, key, action));
+        let mut bindings = Vec::with_capacity(self.bindings.len() + keys.len());
+        let mut new = Some(new);
+        for &(m, k, a) in &self.bindings {
+            if m != mode {
+                bindings.push((m, k, a));
+                continue;
+            }
+            if a == action {
+                // in place of the old ones, to keep the order of the help
+                bindings.extend(new.take().into_iter().flatten());
+            } else if !keys.contains(&k) {
+                bindings.push((m, k, a));
+            }
+        }
+        bindings.extend(new.into_iter().flatten());
+        self.bindings = bindings;
+        Ok(())
+    }
+    /// The keys bound to `action` in `mode`, in order
+    pub fn keys_for(&self, mode: Mode, action: Action) -> Vec<Key> {
+        self.bindings
+            .iter()
+            .filter(|&&(m, _, a)| m == mode && a == action)
+            .map(|&(_, key, _)| key)
+            .collect()
+    }
+    /// The first key of each of `actions` in `mode` in brackets, eg. `[q]`, `[hjkl]` or `[left/right]`,
+    /// `None` if none of them is bound
+    pub fn hint(&self, mode: Mode, actions: &[Action]) -> Option<String> {
+        let keys = actions
+            .iter()
+            .filter_map(|&action| self.keys_for(mode, action).first().map(Key::to_string))
+            .collect::<Vec<_>>();
+        if keys.is_empty() {
+            return None;
+        }
+        let separator = if keys.iter().all(|key| key.chars().count() == 1) {
+            ""
+        } else {
+            "/"
+        };
+        Some(format!("[{}]", keys.join(separator)))
+    }
+    /// The keys of each action of `mode`, in order
+    pub fn help(&self, mode: Mode) -> Vec<(Vec<Key>, Action)> {
+        let mut help: Vec<(Vec<Key>, Action)> = vec![];
+        for &(m, key, action) in &self.bindings {
+            if m != mode {
+                continue;
+            }
+            match help.iter_mut().find(|(_, a)| *a == action) {
+                Some((keys, _)) => keys.push(key),
+                None => help.push((vec![key], action)),
+            }
+        }
+        help
+    }
+}
@@ -0,0 +1,162 @@
+use super::{format::Format, Error, Universe};
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+/// Extensions of the supported pattern files
+pub const EXTENSIONS: &[&str] = &["cells", "rle", "lif", "life"];
+/// Environment variable overriding the default pattern library path
+pub const LIBRARY_VAR: &str = "CGOL_TUI_LIBRARY";
+
+/// Whether `path` looks like a supported pattern file
+pub fn is_pattern_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+}
+
+/// All the supported pattern files in `dir` and its subdirectories, sorted by name
+///
+/// # Errors
+///
+/// if `dir` or one of it's subdirectories can't be read
+pub fn pattern_files(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files = vec![];
+    let mut dirs = vec![dir.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                dirs.push(path);
+            } else if is_pattern_file(&path) {
+                files.push(path);
+            }
+        }
+    }
+    files.sort_by_cached_key(|path| {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_lowercase());
+        (name, path.clone())
+    });
+    Ok(files)
+}
+
+/// The default pattern library: `$CGOL_TUI_LIBRARY` if set,
+/// otherwise `$XDG_DATA_HOME/cgol-tui/patterns`, falling back to `~/.local/share/cgol-tui/patterns`
+pub fn default_library() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os(LIBRARY_VAR).filter(|p| !p.is_empty()) {
+        return Some(PathBuf::from(path));
+    }
+    let data_home = std::env::var_os("XDG_DATA_HOME")
+        .filter(|p| !p.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".local/share")))?;
+    Some(data_home.join("cgol-tui").join("patterns"))
+}
+
+/// `path` with a leading `~/` standing for the home directory
+pub fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), std::env::var_os("HOME")) {
+        (Some(rest), Some(home)) => Path::new(&home).join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Failure to load a pattern from `path`
+#[derive(Debug)]
+pub struct LoadError {
+    /// `-` for stdin
+    pub path: PathBuf,
+    pub source: Error,
+}
+impl LoadError {
+    pub fn new(path: impl Into<PathBuf>, source: impl Into<Error>) -> Self {
+        Self {
+            path: path.into(),
+            source: source.into(),
+        }
+    }
+    /// 1-based line number, if known
+    pub fn line(&self) -> Option<usize> {
+        match &self.source {
+            Error::Parse(e) => Some(e.line),
+            _ => None,
+        }
+    }
+    /// 1-based column number, if known
+    pub fn col(&self) -> Option<usize> {
+        match &self.source {
+            Error::Parse(e) => Some(e.col),
+            _ => None,
+        }
+    }
+    pub fn reason(&self) -> String {
+        match &self.source {
+            Error::Parse(e) => e.reason.clone(),
+            e => e.to_string(),
+        }
+    }
+}
+impl std::fmt::Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.path.display())?;
+        if let (Some(line), Some(col)) = (self.line(), self.col()) {
+            write!(f, ":{line}:{col}")?;
+        }
+        write!(f, ": {}", self.reason())
+    }
+}
+impl std::error::Error for LoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// Parse `s`, read from `path`, its format guessed from the extension or the content
+///
+/// # Errors
+///
+/// if `s` isn't a valid pattern
+pub fn parse(path: &Path, s: &str) -> Result<Universe, LoadError> {
+    let format = Format::from_path(path).unwrap_or_else(|| Format::detect(s));
+    parse_as(path, s, format)
+}
+/// Parse `s`, read from `path` as `format`.
+/// Unnamed patterns are named after the file.
+///
+/// # Errors
+///
+/// if `s` isn't a valid pattern
+pub fn parse_as(path: &Path, s: &str, format: Format) -> Result<Universe, LoadError> {
+    let univ = format.parse(s).map_err(|e| LoadError::new(path, e))?;
+    if !univ.name().is_empty() {
+        return Ok(univ);
+    }
+    let name = match path.file_stem() {
+        _ if path.as_os_str() == "-" => "stdin".into(),
+        Some(stem) => stem.to_string_lossy(),
+        None => path.to_string_lossy(),
+    };
+    Ok(univ.with_name(name))
+}
+
+/// Load the pattern file at `path`, or every pattern file under it, if it's a directory
+pub fn load(path: &Path) -> Vec<Result<Universe, LoadError>> {
+    let files = if path.is_dir() {
+        match pattern_files(path) {
+            Ok(files) => files,
+            Err(e) => return vec![Err(LoadError::new(path, e))],
+        }
+    } else {
+        vec![path.to_path_buf()]
+    };
+    files
+        .into_iter()
+        .map(|file| match std::fs::read_to_string(&file) {
+            Ok(s) => parse(&file, &s),
+            Err(e) => Err(LoadError::new(file, e)),
+        })
+        .collect()
+}
@@ -0,0 +1,57 @@
+use super::Universe;
+
+/// Orientation of a pattern: mirrored left to right if `flipped`, then rotated clockwise
+/// by `turns` × 90°. Every combination of rotations, flips and transposing ends up as one of these.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Orientation {
+    pub flipped: bool,
+    /// quarter turns, 0..4
+    pub turns: u8,
+}
+impl Orientation {
+    /// `univ` in this orientation
+    pub fn apply(self, univ: &Universe) -> Universe {
+        if self.flipped {
+            univ.flip_horizontal().rotate(self.turns)
+        } else {
+            univ.rotate(self.turns)
+        }
+    }
+    /// Whether width and height are swapped
+    pub fn swaps_axes(self) -> bool {
+        self.turns % 2 == 1
+    }
+
+    /// Then rotated clockwise by `turns` × 90°
+    pub fn rotate(self, turns: u8) -> Self {
+        Self {
+            turns: (self.turns + turns) % 4,
+            ..self
+        }
+    }
+    /// Then mirrored left to right: flipping reverses the direction of earlier rotations
+    pub fn flip_horizontal(self) -> Self {
+        Self {
+            flipped: !self.flipped,
+            turns: (4 - self.turns) % 4,
+        }
+    }
+    /// Then mirrored top to bottom: a horizontal flip, rotated by 180°
+    pub fn flip_vertical(self) -> Self {
+        self.flip_horizontal().rotate(2)
+    }
+    /// Then mirrored along the main diagonal: a horizontal flip, rotated by 270°
+    pub fn transpose(self) -> Self {
+        self.flip_horizontal().rotate(3)
+    }
+}
+impl std::fmt::Display for Orientation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.flipped, self.turns) {
+            (false, 0) => Ok(()),
+            (false, turns) => write!(f, "rotated {}°", u16::from(turns) * 90),
+            (true, 0) => write!(f, "mirrored"),
+            (true, turns) => write!(f, "mirrored, rotated {}°", u16::from(turns) * 90),
+        }
+    }
+}
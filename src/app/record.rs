@@ -0,0 +1,238 @@
+use super::{Area, Cell, Error, Theme, Universe};
+use ratatui::style::Color;
+use std::{
+    io::{self, Write},
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+/// Default size of a cell: pixels
+pub const CELL_SIZE: u16 = 4;
+/// Default time a generation is shown: hundredths of a second
+pub const DELAY: u16 = 10;
+/// Shortest time a frame is shown: most viewers slow down shorter ones
+const MIN_DELAY: u16 = 2;
+
+/// How cells are drawn into the frames
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Style {
+    /// width and height of a cell: pixels
+    pub cell: u16,
+    /// colour of live cells: red, green, blue
+    pub alive: [u8; 3],
+    /// colour of dead cells: red, green, blue
+    pub dead: [u8; 3],
+}
+impl Default for Style {
+    fn default() -> Self {
+        Self {
+            cell: CELL_SIZE,
+            alive: [255, 255, 255],
+            dead: [0, 0, 0],
+        }
+    }
+}
+impl Style {
+    /// Colours of `theme`, a background contrasting with the cells if it's the terminal's own
+    pub fn from_theme(theme: Theme) -> Self {
+        let alive = rgb(theme.alive).unwrap_or([255, 255, 255]);
+        let bright = alive.iter().map(|&c| u16::from(c)).sum::<u16>() > 3 * 127;
+        let dead = rgb(theme.background).unwrap_or(if bright { [0; 3] } else { [255; 3] });
+        Self {
+            alive,
+            dead,
+            ..Self::default()
+        }
+    }
+}
+
+/// `color` as red, green, blue, the way xterm shows it, `None` if it depends on the terminal
+pub fn rgb(color: Color) -> Option<[u8; 3]> {
+    Some(match color {
+        Color::Rgb(r, g, b) => [r, g, b],
+        Color::Black => [0, 0, 0],
+        Color::Red => [205, 0, 0],
+        Color::Green => [0, 205, 0],
+        Color::Yellow => [205, 205, 0],
+        Color::Blue => [0, 0, 238],
+        Color::Magenta => [205, 0, 205],
+        Color::Cyan => [0, 205, 205],
+        Color::Gray => [229, 229, 229],
+        Color::DarkGray => [127, 127, 127],
+        Color::LightRed => [255, 0, 0],
+        Color::LightGreen => [0, 255, 0],
+        Color::LightYellow => [255, 255, 0],
+        Color::LightBlue => [92, 92, 255],
+        Color::LightMagenta => [255, 0, 255],
+        Color::LightCyan => [0, 255, 255],
+        Color::White => [255, 255, 255],
+        Color::Reset | Color::Indexed(_) => return None,
+    })
+}
+
+/// Parse a colour as hexadecimal `RRGGBB`, optionally prefixed with `#`
+///
+/// # Errors
+///
+/// not 6 hexadecimal digits
+pub fn parse_color(s: &str) -> Result<[u8; 3], Error> {
+    let invalid = || Error::Invalid {
+        what: "colour, expected RRGGBB",
+        value: s.to_string(),
+    };
+    let hex = s.strip_prefix('#').unwrap_or(s);
+    if hex.len() != 6 || !hex.is_ascii() {
+        return Err(invalid());
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| invalid());
+    Ok([channel(0)?, channel(2)?, channel(4)?])
+}
+
+/// Writes generations as the frames of an animated GIF, looping forever
+pub struct Recorder<W: Write> {
+    encoder: gif::Encoder<W>,
+    /// of the frames, in cells
+    area: Area,
+    style: Style,
+    /// number of frames written
+    pub frames: usize,
+}
+impl<W: Write> Recorder<W> {
+    /// Start a GIF of universes of `area`
+    ///
+    /// # Errors
+    ///
+    /// the frames would be empty or too big, or writing failed
+    pub fn new(out: W, area: Area, style: Style) -> Result<Self, Error> {
+        let size = |cells: u16| cells.checked_mul(style.cell).filter(|&px| px > 0);
+        let (Some(width), Some(height)) = (size(area.width), size(area.height)) else {
+            return Err(Error::Invalid {
+                what: "GIF size",
+                value: format!(
+                    "{}×{} cells of {} pixels",
+                    area.width, area.height, style.cell
+                ),
+            });
+        };
+        let palette = [style.dead, style.alive].concat();
+        let mut encoder = gif::Encoder::new(out, width, height, &palette).map_err(gif_error)?;
+        encoder
+            .set_repeat(gif::Repeat::Infinite)
+            .map_err(gif_error)?;
+        Ok(Self {
+            encoder,
+            area,
+            style,
+            frames: 0,
+        })
+    }
+    /// Add `univ` as a frame shown for `delay` hundredths of a second.
+    /// Only its top left corner is drawn if it's bigger than the GIF.
+    ///
+    /// # Errors
+    ///
+    /// writing failed
+    pub fn frame(&mut self, univ: &Universe, delay: u16) -> Result<(), Error> {
+        let cell = usize::from(self.style.cell);
+        let width = usize::from(self.area.width) * cell;
+        let mut pixels = Vec::with_capacity(width * usize::from(self.area.height) * cell);
+        for row in 0..self.area.height {
+            let line = (0..self.area.width)
+                .flat_map(|col| {
+                    let alive = row < univ.height()
+                        && col < univ.width()
+                        && univ[(row, col)] == Cell::Alive;
+                    std::iter::repeat_n(u8::from(alive), cell)
+                })
+                .collect::<Vec<_>>();
+            for _ in 0..cell {
+                pixels.extend_from_slice(&line);
+            }
+        }
+        let mut frame = gif::Frame::from_indexed_pixels(
+            self.area.width * self.style.cell,
+            self.area.height * self.style.cell,
+            pixels,
+            None,
+        );
+        frame.delay = delay;
+        self.encoder.write_frame(&frame).map_err(gif_error)?;
+        self.frames += 1;
+        Ok(())
+    }
+    /// Finish the GIF
+    ///
+    /// # Errors
+    ///
+    /// writing failed
+    pub fn finish(self) -> Result<W, Error> {
+        self.encoder.into_inner().map_err(gif_error)
+    }
+}
+
+/// A GIF being recorded from the generations drawn, each shown as long as it was on screen
+pub struct Recording<W: Write> {
+    pub path: PathBuf,
+    pub recorder: Recorder<W>,
+    /// the generation on screen and since when, written once it's replaced
+    shown: Option<(u64, Universe, Instant)>,
+}
+impl<W: Write> Recording<W> {
+    pub fn new(path: PathBuf, recorder: Recorder<W>) -> Self {
+        Self {
+            path,
+            recorder,
+            shown: None,
+        }
+    }
+    /// Number of frames, counting the generation on screen
+    pub fn frames(&self) -> usize {
+        self.recorder.frames + usize::from(self.shown.is_some())
+    }
+    /// Show `univ` at `generation` from now on, writing the generation shown until now.
+    /// One shown too briefly to be a frame of its own is replaced instead.
+    ///
+    /// # Errors
+    ///
+    /// writing failed
+    pub fn show(&mut self, generation: u64, univ: &Universe) -> Result<(), Error> {
+        let now = Instant::now();
+        let since = match self.shown.take() {
+            Some((shown, ..)) if shown == generation => return Ok(()),
+            Some((_, shown, since)) => {
+                let delay = (now - since).as_millis() / 10;
+                match u16::try_from(delay) {
+                    Ok(delay) if delay < MIN_DELAY => since,
+                    delay => {
+                        let delay = delay.unwrap_or(u16::MAX);
+                        self.recorder.frame(&shown, delay)?;
+                        // the rest of the hundredth of a second goes to the next frame
+                        since + Duration::from_millis(u64::from(delay) * 10)
+                    }
+                }
+            }
+            None => now,
+        };
+        self.shown = Some((generation, univ.clone(), since));
+        Ok(())
+    }
+    /// Write the generation shown last, then finish the GIF
+    ///
+    /// # Errors
+    ///
+    /// writing failed
+    pub fn finish(mut self) -> Result<W, Error> {
+        if let Some((_, shown, since)) = self.shown.take() {
+            let delay = u16::try_from(since.elapsed().as_millis() / 10).unwrap_or(u16::MAX);
+            self.recorder.frame(&shown, delay.max(MIN_DELAY))?;
+        }
+        self.recorder.finish()
+    }
+}
+
+fn gif_error(e: gif::EncodingError) -> Error {
+    match e {
+        gif::EncodingError::Io(e) => Error::Io(e),
+        e => Error::Io(io::Error::other(e)),
+    }
+}
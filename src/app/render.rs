@@ -0,0 +1,156 @@
+use super::{universe::View, Area, Cell, Error};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Color,
+    widgets::{canvas::Shape, Widget},
+};
+use std::collections::HashMap;
+
+/// How cells are drawn in the terminal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Renderer {
+    /// braille dots: 2×4 cells per character
+    #[default]
+    Braille,
+    /// upper and lower half blocks: 1×2 cells per character
+    HalfBlock,
+    /// full blocks, two characters per cell, squares in most fonts
+    Block,
+    /// `O` and `.`: a cell per character
+    Ascii,
+}
+impl Renderer {
+    pub const ALL: [Renderer; 4] = [Self::Braille, Self::HalfBlock, Self::Block, Self::Ascii];
+
+    /// Number of cells fitting into `chars` characters
+    pub fn area(self, chars: Area) -> Area {
+        match self {
+            Self::Braille => Area::new(chars.width * 2, chars.height * 4),
+            Self::HalfBlock => Area::new(chars.width, chars.height * 2),
+            Self::Block => Area::new(chars.width / 2, chars.height),
+            Self::Ascii => chars,
+        }
+    }
+    /// The one after this one, wrapping around
+    #[must_use]
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|&r| r == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+}
+impl std::fmt::Display for Renderer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Braille => write!(f, "braille"),
+            Self::HalfBlock => write!(f, "halfblock"),
+            Self::Block => write!(f, "block"),
+            Self::Ascii => write!(f, "ascii"),
+        }
+    }
+}
+impl std::str::FromStr for Renderer {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|r| r.to_string() == s.to_lowercase())
+            .ok_or_else(|| Error::Invalid {
+                what: "renderer",
+                value: s.to_string(),
+            })
+    }
+}
+
+/// Cells drawn over the universe on the braille canvas: (row, col) and colour
+pub struct Overlay<'a> {
+    pub cells: &'a [((u16, u16), Color)],
+    /// top left corner of the visible part: (row, col)
+    pub offset: (u16, u16),
+    pub area: Area,
+}
+impl Shape for Overlay<'_> {
+    fn draw(&self, painter: &mut ratatui::widgets::canvas::Painter) {
+        let (off_row, off_col) = self.offset;
+        for &((row, col), color) in self.cells {
+            if (off_row..off_row + self.area.height).contains(&row)
+                && (off_col..off_col + self.area.width).contains(&col)
+            {
+                painter.paint((col - off_col).into(), (row - off_row).into(), color);
+            }
+        }
+    }
+}
+
+/// The visible part of the universe drawn with characters instead of braille dots
+pub struct Grid<'a> {
+    pub view: View<'a>,
+    pub renderer: Renderer,
+    /// cells drawn over the universe: (row, col) and colour
+    pub overlay: &'a [((u16, u16), Color)],
+}
+impl Grid<'_> {
+    /// Colour of the cell at (row, col) of the universe, `None` if dead or outside
+    fn color(&self, overlay: &HashMap<(u16, u16), Color>, at: (u16, u16)) -> Option<Color> {
+        if let Some(&color) = overlay.get(&at) {
+            return Some(color);
+        }
+        self.view.color(at)
+    }
+    fn inside(&self, (row, col): (u16, u16)) -> bool {
+        row < self.view.universe.height() && col < self.view.universe.width()
+    }
+}
+impl Widget for Grid<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        // later ones on top
+        let overlay = self.overlay.iter().copied().collect::<HashMap<_, _>>();
+        let (off_row, off_col) = self.view.offset;
+        let background = self.view.theme.background;
+        for y in 0..area.height {
+            for x in 0..area.width {
+                let cell = &mut buf[(area.x + x, area.y + y)];
+                cell.set_bg(background);
+                match self.renderer {
+                    Renderer::Braille => {}
+                    Renderer::HalfBlock => {
+                        let top = (off_row + 2 * y, off_col + x);
+                        let bottom = (off_row + 2 * y + 1, off_col + x);
+                        match (self.color(&overlay, top), self.color(&overlay, bottom)) {
+                            (None, None) => cell.set_char(' '),
+                            (None, Some(bottom)) => cell.set_char('▄').set_fg(bottom),
+                            (Some(top), bottom) => cell
+                                .set_char('▀')
+                                .set_fg(top)
+                                .set_bg(bottom.unwrap_or(background)),
+                        };
+                    }
+                    Renderer::Block => {
+                        let at = (off_row + y, off_col + x / 2);
+                        match self.color(&overlay, at) {
+                            Some(color) if x / 2 < area.width / 2 => {
+                                cell.set_char('█').set_fg(color)
+                            }
+                            _ => cell.set_char(' '),
+                        };
+                    }
+                    Renderer::Ascii => {
+                        let at = (off_row + y, off_col + x);
+                        let color = self.color(&overlay, at);
+                        let alive = self.inside(at) && self.view.universe[at] == Cell::Alive;
+                        match color {
+                            Some(color) if alive || overlay.contains_key(&at) => {
+                                cell.set_char('O').set_fg(color)
+                            }
+                            // just died or changed often
+                            Some(color) => cell.set_char('.').set_fg(color),
+                            None if self.inside(at) => cell.set_char('.').set_fg(Color::DarkGray),
+                            None => cell.set_char(' '),
+                        };
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use super::{Cell, Error};
+
+/// Life-like rule in B/S notation: how many live neighbours make a dead cell born,
+/// or a live one survive, eg. B3/S23 for Conway's Game of Life
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rule {
+    /// `birth[n]`: whether a dead cell with `n` live neighbours becomes alive
+    pub birth: [bool; 9],
+    /// `survival[n]`: whether a live cell with `n` live neighbours stays alive
+    pub survival: [bool; 9],
+}
+impl Default for Rule {
+    /// Conway's Game of Life: B3/S23
+    ///
+    /// 1. Any live cell with fewer than two live neighbours dies, as if caused by underpopulation.
+    /// 2. Any live cell with two or three live neighbours lives on to the next generation.
+    /// 3. Any live cell with more than three live neighbours dies, as if by overpopulation.
+    /// 4. Any dead cell with exactly three live neighbours becomes a live cell, as if by reproduction.
+    fn default() -> Self {
+        Self::new(&[3], &[2, 3])
+    }
+}
+impl Rule {
+    pub fn new(birth: &[u8], survival: &[u8]) -> Self {
+        let to_table = |ns: &[u8]| {
+            let mut table = [false; 9];
+            for &n in ns {
+                table[n as usize] = true;
+            }
+            table
+        };
+        Self {
+            birth: to_table(birth),
+            survival: to_table(survival),
+        }
+    }
+
+    /// The next state of `cell`, having `live_neighbours`
+    pub fn apply(&self, cell: Cell, live_neighbours: u8) -> Cell {
+        let n = live_neighbours as usize;
+        match cell {
+            Cell::Alive => self.survival[n].into(),
+            Cell::Dead => self.birth[n].into(),
+        }
+    }
+}
+impl std::str::FromStr for Rule {
+    type Err = Error;
+
+    /// Parse either `B3/S23` or the older `23/3`: survival/birth
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::Invalid {
+            what: "rule",
+            value: s.to_string(),
+        };
+        let digits = |part: &str| -> Result<Vec<u8>, Error> {
+            part.chars()
+                .map(|ch| match ch.to_digit(9) {
+                    Some(n) => Ok(n as u8),
+                    None => Err(invalid()),
+                })
+                .collect()
+        };
+
+        let (first, second) = s.trim().split_once('/').ok_or_else(invalid)?;
+        let strip = |part: &str, prefix: char| {
+            part.strip_prefix(prefix)
+                .or_else(|| part.strip_prefix(prefix.to_ascii_lowercase()))
+                .map(str::to_string)
+        };
+        let (birth, survival) = match (strip(first, 'B'), strip(second, 'S')) {
+            (Some(birth), Some(survival)) => (birth, survival),
+            // survival/birth
+            (None, None) => (second.to_string(), first.to_string()),
+            _ => return Err(invalid()),
+        };
+        Ok(Self::new(&digits(&birth)?, &digits(&survival)?))
+    }
+}
+impl std::fmt::Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let digits = |table: &[bool; 9]| {
+            (0..9)
+                .filter(|&n| table[n])
+                .map(|n| n.to_string())
+                .collect::<String>()
+        };
+        write!(f, "B{}/S{}", digits(&self.birth), digits(&self.survival))
+    }
+}
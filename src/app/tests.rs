@@ -1,10 +1,52 @@
 use super::*;
+use ratatui::{backend::TestBackend, buffer::Buffer};
 
 fn gen_uni(area: Area, cells: &[bool]) -> Universe {
     let cells = cells.iter().map(|c| (*c).into()).collect::<Vec<Cell>>();
     Universe::new(area, cells, "test")
 }
 
+/// `app` drawn on a terminal of `width`×`height`
+fn draw(app: &mut App, width: u16, height: u16) -> Buffer {
+    let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+    terminal.draw(|f| ui::ui(f, app)).unwrap();
+    terminal.backend().buffer().clone()
+}
+/// What `app` shows on a terminal of `width`×`height`, row after row
+fn screen(app: &mut App, width: u16, height: u16) -> String {
+    let buf = draw(app, width, height);
+    buf.content().iter().map(|cell| cell.symbol()).collect()
+}
+
+/// A directory of its own for the test `name`, removed with everything in it when dropped
+pub(crate) struct TempDir(PathBuf);
+impl TempDir {
+    pub(crate) fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("cgol-tui-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        Self(dir)
+    }
+    /// `file` in the directory, written with `contents`
+    pub(crate) fn file(&self, file: &str, contents: &str) -> PathBuf {
+        let path = self.0.join(file);
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+}
+impl std::ops::Deref for TempDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
 #[test]
 fn parse0() {
     let figur = "\
@@ -409,3 +451,1240 @@ fn neighbours() {
     assert_eq!(nghbrs((3, 2)), 3);
     assert_eq!(nghbrs((3, 3)), 3);
 }
+
+#[test]
+fn pacer_slow() {
+    let mut pacer = Pacer::default();
+    // 2 generations per second: one every 500ms
+    assert_eq!(pacer.due(Duration::from_millis(300), 2.), 0);
+    assert_eq!(pacer.due(Duration::from_millis(300), 2.), 1);
+    assert_eq!(pacer.due(Duration::from_millis(400), 2.), 1);
+    assert_eq!(pacer.due(Duration::from_millis(100), 2.), 0);
+}
+#[test]
+fn pacer_fast() {
+    let mut pacer = Pacer::default();
+    // multiple generations per frame
+    assert_eq!(pacer.due(Duration::from_millis(20), 500.), 10);
+    assert_eq!(pacer.due(Duration::from_secs(1), 1000.), 1000);
+    pacer.reset();
+    assert_eq!(pacer.due(Duration::ZERO, 1000.), 0);
+}
+
+#[test]
+fn fuzzy() {
+    use browser::fuzzy_score;
+    assert!(fuzzy_score("", "Glider").is_some());
+    assert!(fuzzy_score("gld", "Glider").is_some());
+    assert!(fuzzy_score("GLIDER", "glider").is_some());
+    assert!(fuzzy_score("gdl", "Glider").is_none());
+    assert!(fuzzy_score("glider gun", "Glider").is_none());
+    // consecutive and word-start matches rank higher
+    assert!(fuzzy_score("gun", "Gosper glider gun") > fuzzy_score("gun", "Glider dunno"));
+    assert!(fuzzy_score("gli", "Glider") > fuzzy_score("gli", "Gosper glider gun"));
+}
+#[test]
+fn browser_matches() {
+    let app = App::default();
+    let entries = app
+        .available_universes
+        .iter()
+        .enumerate()
+        .map(|(i, univ)| browser::Entry::new(i, univ))
+        .collect();
+    let mut browser = Browser::new(entries, 1);
+    assert_eq!(browser.selected().unwrap().name, "Gosper glider gun");
+    for ch in "glider".chars() {
+        browser.push(ch);
+    }
+    let names = browser
+        .matches()
+        .iter()
+        .map(|e| e.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["Glider", "Gosper glider gun"]);
+    browser.select_prev();
+    assert_eq!(browser.selected().unwrap().i, 1);
+}
+
+#[test]
+fn pattern_files() {
+    let dir = TempDir::new("pattern-files");
+    std::fs::create_dir_all(dir.join("b/c")).unwrap();
+    std::fs::create_dir_all(dir.join("a")).unwrap();
+    for path in [
+        "b/c/zeta.cells",
+        "a/alpha.cells",
+        "Beta.CELLS",
+        "readme.txt",
+    ] {
+        std::fs::write(dir.join(path), shapes::GLIDER).unwrap();
+    }
+    let files = load::pattern_files(&dir).unwrap();
+
+    let files = files
+        .iter()
+        .map(|path| path.strip_prefix(&*dir).unwrap().to_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(files, ["a/alpha.cells", "Beta.CELLS", "b/c/zeta.cells"]);
+}
+
+#[test]
+fn parse_error_position() {
+    let figur = "
+
+!Name: faulty
+..O
+  O.O
+  .O?";
+    let Err(Error::Parse(err)) = Universe::from_str(figur) else {
+        panic!("should be a parse error");
+    };
+    assert_eq!((err.line, err.col), (6, 5));
+
+    let err = load::parse("faulty.cells".as_ref(), figur).unwrap_err();
+    assert_eq!(err.line(), Some(6));
+    assert_eq!(err.col(), Some(5));
+    assert!(err.to_string().starts_with("faulty.cells:6:5: "));
+}
+
+#[test]
+fn too_big() {
+    let figur = Universe::from_str(shapes::BONK_TIE).unwrap();
+    let area = Area::new(4, 4);
+    assert!(matches!(
+        Universe::from_figur(area, figur.clone()),
+        Err(Error::TooBig { figure, area: a }) if figure == figur.area && a == area
+    ));
+    // wide, but not tall enough
+    assert!(Universe::from_figur(Area::new(40, 4), figur.clone()).is_err());
+    assert!(Universe::from_figur(Area::new(4, 5), figur).is_ok());
+}
+#[test]
+fn restart_too_big() {
+    let mut app = App {
+        area: Area::new(20, 4),
+        ..Default::default()
+    };
+    // Gosper glider gun
+    app.next();
+    assert!(app.message.is_none());
+    assert_eq!(app.universe.area, Area::new(36, 9));
+    assert!(app.scrollable());
+    // centered
+    assert_eq!(app.offset, (2, 8));
+
+    app.scroll(-100, 3);
+    assert_eq!(app.offset, (0, 11));
+    app.scroll(100, 100);
+    assert_eq!(app.offset, (5, 16));
+}
+#[test]
+fn tiny_terminal() {
+    let mut app = App::default();
+    for (width, height) in [(0, 0), (1, 1), (3, 2), (15, 40), (80, 4)] {
+        draw(&mut app, width, height);
+    }
+    // in the colours of the theme
+    for theme in Theme::ALL {
+        app.theme = theme;
+        let buffer = draw(&mut app, 15, 4);
+        let cell = buffer
+            .content()
+            .iter()
+            .find(|cell| cell.symbol() == "t")
+            .unwrap();
+        assert_eq!(cell.fg, theme.error, "{theme}");
+    }
+}
+
+#[test]
+fn rule_parse() {
+    let conway = Rule::default();
+    assert_eq!(Rule::from_str("B3/S23").unwrap(), conway);
+    assert_eq!(Rule::from_str("b3/s23").unwrap(), conway);
+    assert_eq!(Rule::from_str("23/3").unwrap(), conway);
+    assert_eq!(conway.to_string(), "B3/S23");
+
+    let highlife = Rule::from_str("B36/S23").unwrap();
+    assert_eq!(highlife, Rule::new(&[3, 6], &[2, 3]));
+    assert_eq!(highlife.to_string(), "B36/S23");
+    assert_eq!(Rule::from_str("B/S").unwrap().to_string(), "B/S");
+
+    for invalid in ["", "B3", "B9/S23", "S23/B3", "B3/23", "Bx/S1"] {
+        assert!(Rule::from_str(invalid).is_err(), "{invalid:?}");
+    }
+}
+#[test]
+fn rule_tick() {
+    // B1/S: a single cell explodes
+    let mut univ = Universe::from_figur(Area::new(5, 5), Universe::from_str("O").unwrap())
+        .unwrap()
+        .with_rule(Rule::from_str("B1/S").unwrap());
+    univ.tick();
+    let exp = Universe::from_str(
+        "\
+.....
+.OOO.
+.O.O.
+.OOO.
+.....",
+    )
+    .unwrap();
+    assert_eq!(univ.cells, exp.cells);
+}
+#[test]
+fn write_cells() {
+    let glider = Universe::from_str(shapes::GLIDER).unwrap();
+    let cells = format::to_cells(&glider);
+    assert_eq!(cells, "!Name: Glider\n..O\nO.O\n.OO\n");
+    assert_eq!(Universe::from_str(&cells).unwrap(), glider);
+}
+#[test]
+fn write_rle() {
+    let glider = Universe::from_str(shapes::GLIDER).unwrap();
+    assert_eq!(
+        format::to_rle(&glider),
+        "#N Glider\nx = 3, y = 3, rule = B3/S23\n2bo$obo$b2o!\n"
+    );
+    let gap = Universe::from_str("O..\n...\n...\n..O\n...").unwrap();
+    assert_eq!(
+        format::to_rle(&gap.with_name("gap")),
+        "#N gap\nx = 3, y = 5, rule = B3/S23\no3$2bo!\n"
+    );
+    // long lines are wrapped
+    let stripes = shapes::stripes(Area::new(100, 2));
+    let rle = format::to_rle(&stripes);
+    assert!(rle.lines().all(|l| l.len() <= 70));
+}
+#[test]
+fn area_parse() {
+    assert_eq!(Area::from_str("200x100").unwrap(), Area::new(200, 100));
+    assert_eq!(Area::from_str("3×4").unwrap(), Area::new(3, 4));
+    assert!(Area::from_str("200").is_err());
+    assert!(Area::from_str("-1x2").is_err());
+    assert!(Area::from_str("0x0").is_err());
+    assert!(Area::from_str("0x2").is_err());
+}
+
+#[test]
+fn plane_topology() {
+    // blinker on the edge
+    let univ = Universe::from_str("OOO.\n....\n....\n....").unwrap();
+    let mut torus = univ.clone();
+    torus.tick();
+    assert_eq!(torus.population(), 3);
+    assert_eq!(torus[(3u16, 1u16)], Cell::Alive);
+
+    let mut plane = univ.with_topology(Topology::Plane);
+    assert_eq!(plane.live_neighbour_count(0, 0), 1);
+    assert_eq!(plane.live_neighbour_count(1, 1), 3);
+    assert_eq!(plane.live_neighbour_count(3, 1), 0);
+    plane.tick();
+    assert_eq!(plane.population(), 2);
+    assert_eq!(plane[(3u16, 1u16)], Cell::Dead);
+}
+#[test]
+fn app_options() {
+    let mut app = App::default()
+        .with_rule(Some(Rule::from_str("B36/S23").unwrap()))
+        .with_topology(Topology::Plane)
+        .with_size(Some(Area::new(60, 30)))
+        .with_index(1)
+        .unwrap();
+    app.set_area(Area::new(20, 10));
+    assert_eq!(app.universe.name(), "Gosper glider gun");
+    assert_eq!(app.universe.area, Area::new(60, 30));
+    assert_eq!(app.universe.rule.to_string(), "B36/S23");
+    assert_eq!(app.universe.topology, Topology::Plane);
+    // resizing the terminal keeps the universe going
+    app.tick();
+    let ticked = app.universe.clone();
+    app.set_area(Area::new(30, 12));
+    assert_eq!(app.universe, ticked);
+    assert!(App::default().with_index(10).is_err());
+}
+
+#[test]
+fn reset() {
+    let own = Universe::from_str("OOO").unwrap().with_name("own");
+    let mut app = App::default()
+        .with_universes(vec![own])
+        .with_speed(10.)
+        .with_renderer(Renderer::Ascii)
+        .with_theme(Theme::ALL[1])
+        .with_rule(Some(Rule::from_str("B36/S23").unwrap()))
+        .with_topology(Topology::Plane)
+        .with_size(Some(Area::new(40, 20)))
+        .with_pattern("glider")
+        .unwrap();
+    app.remember_startup();
+    app.set_area(Area::new(20, 10));
+    let started = app.universe.clone();
+    app.speed = 50.;
+    app.renderer = Renderer::Block;
+    app.theme = Theme::ALL[2];
+    app.execute("rule B3/S23");
+    app.execute("size auto");
+    app.next();
+    assert_ne!(app.universe.name(), "Glider");
+
+    app.handle_key(KeyEvent::from(KeyCode::Char('R')));
+    app.set_area(Area::new(20, 10));
+    assert_eq!(app.universe, started);
+    assert_eq!(app.universe.area, Area::new(40, 20));
+    assert_eq!(app.speed, 10.);
+    assert_eq!(app.renderer, Renderer::Ascii);
+    assert_eq!(app.theme, Theme::ALL[1]);
+    assert_eq!(app.available_universes[0].name(), "own");
+    // and again
+    app.handle_key(KeyEvent::from(KeyCode::Char('R')));
+    app.set_area(Area::new(20, 10));
+    assert_eq!(app.universe, started);
+}
+
+#[test]
+fn parse_rle() {
+    let glider = Universe::from_str(shapes::GLIDER).unwrap();
+    let rle = "\
+#N Glider
+#C a comment
+x = 3, y = 3, rule = B3/S23
+2bo$
+obo$b2o!";
+    assert_eq!(format::Format::detect(rle), format::Format::Rle);
+    assert_eq!(format::from_rle(rle).unwrap(), glider);
+    assert_eq!(format::parse(&format::to_rle(&glider)).unwrap(), glider);
+
+    let highlife = format::from_rle("x=2,y=3,rule=B36/S23\n2o2$bo!").unwrap();
+    assert_eq!(highlife.rule, Rule::from_str("B36/S23").unwrap());
+    assert_eq!(
+        highlife.cells,
+        Universe::from_str("OO\n..\n.O").unwrap().cells
+    );
+
+    let Err(Error::Parse(e)) = format::from_rle("x = 3, y = 3\nbo$\n2b%!") else {
+        panic!("should be a parse error");
+    };
+    assert_eq!((e.line, e.col), (3, 3));
+    assert!(matches!(
+        format::from_rle("x = 2, y = 2\n3o!"),
+        Err(Error::TooBig { .. })
+    ));
+    // huge runs and counts are rejected without being expanded
+    for rle in [
+        "x = 2, y = 2\n2000000000o!",
+        "x = 2, y = 2\n$$$o!",
+        "x = 2, y = 2\n99999999999999999999999b9o!",
+    ] {
+        assert!(
+            matches!(format::from_rle(rle), Err(Error::TooBig { .. })),
+            "{rle}"
+        );
+    }
+    assert_eq!(
+        format::from_rle("x = 2, y = 2\nbo$2000000000b!")
+            .unwrap()
+            .population(),
+        1
+    );
+    assert!(format::from_rle("2bo$obo$b2o!").is_err());
+}
+#[test]
+fn parse_life106() {
+    let glider = Universe::from_str(shapes::GLIDER).unwrap();
+    let life = "#Life 1.06\n1 -1\n-1 0\n1 0\n0 1\n1 1\n";
+    assert_eq!(format::Format::detect(life), format::Format::Life106);
+    assert_eq!(format::from_life106(life).unwrap().cells, glider.cells);
+    let written = format::to_life106(&glider);
+    assert_eq!(format::parse(&written).unwrap().cells, glider.cells);
+
+    let Err(Error::Parse(e)) = format::from_life106("#Life 1.06\n0 0\n  1 x\n") else {
+        panic!("should be a parse error");
+    };
+    assert_eq!((e.line, e.col), (3, 3));
+    let far = format!("#Life 1.06\n{} 0\n{} 0\n", i64::MIN, i64::MAX);
+    assert!(matches!(
+        format::from_life106(&far),
+        Err(Error::Invalid { .. })
+    ));
+}
+#[test]
+fn transforms() {
+    let figur = Universe::from_str("OO.\n..O").unwrap().with_name("L");
+    let rotated = figur.rotate(1);
+    assert_eq!(rotated.area, Area::new(2, 3));
+    assert_eq!(rotated.name(), "L");
+    assert_eq!(
+        rotated.cells,
+        Universe::from_str(".O\n.O\nO.").unwrap().cells
+    );
+    assert_eq!(
+        figur.rotate(2).cells,
+        Universe::from_str("O..\n.OO").unwrap().cells
+    );
+    assert_eq!(figur.rotate(3), rotated.rotate(2));
+    assert_eq!(figur.rotate(4), figur);
+
+    assert_eq!(
+        figur.flip_horizontal().cells,
+        Universe::from_str(".OO\nO..").unwrap().cells
+    );
+    assert_eq!(
+        figur.flip_vertical().cells,
+        Universe::from_str("..O\nOO.").unwrap().cells
+    );
+
+    let padded = figur.pad(2).unwrap();
+    assert_eq!(padded.area, Area::new(7, 6));
+    assert_eq!(padded.population(), 3);
+    assert_eq!(padded.trim(), figur);
+    assert!(figur.pad(u16::MAX).is_err());
+    assert!(figur.pad(u16::MAX / 2).is_err());
+    assert_eq!(figur.pad(100).unwrap().area, Area::new(203, 202));
+    assert_eq!(shapes::empty(Area::new(3, 3)).trim().area, Area::default());
+}
+
+#[test]
+fn orientation() {
+    let figur = Universe::from_str("OO.\n..O").unwrap().with_name("L");
+    let transposed = figur.transpose();
+    assert_eq!(transposed.area, Area::new(2, 3));
+    assert_eq!(transposed.name(), "L");
+    assert_eq!(
+        transposed.cells,
+        Universe::from_str("O.\nO.\n.O").unwrap().cells
+    );
+
+    let id = Orientation::default();
+    assert_eq!(id.transpose().apply(&figur), transposed);
+    assert_eq!(id.flip_vertical().apply(&figur), figur.flip_vertical());
+    assert_eq!(
+        id.rotate(1).flip_horizontal().apply(&figur),
+        figur.rotate(1).flip_horizontal()
+    );
+    assert_eq!(id.rotate(3).rotate(1), id);
+    assert_eq!(id.transpose().transpose(), id);
+
+    let mut app = App::default().with_universes(vec![figur.clone()]);
+    app.set_area(Area::new(8, 8));
+    app.handle_key(KeyEvent::from(KeyCode::Char('>')));
+    assert_eq!(app.orientation.to_string(), "rotated 90°");
+    assert_eq!(app.universe.clone().trim(), figur.rotate(1));
+
+    // an edited board is reoriented in place, as an edit
+    app.toggle_editor();
+    app.handle_key(KeyEvent::from(KeyCode::Char(' ')));
+    app.toggle_editor();
+    let edited = app.universe.clone();
+    assert_eq!(edited.population(), figur.population() + 1);
+    app.handle_key(KeyEvent::from(KeyCode::Char('|')));
+    assert_eq!(app.orientation.to_string(), "mirrored, rotated 270°");
+    assert_eq!(app.universe.area, edited.area);
+    assert_eq!(app.universe.clone().trim(), edited.trim().flip_horizontal());
+    app.handle_key(KeyEvent::from(KeyCode::Char('u')));
+    assert_eq!(app.universe.cells, edited.cells);
+    app.handle_key(KeyEvent::from(KeyCode::Char('u')));
+    assert_eq!(app.universe.clone().trim(), figur.rotate(1));
+}
+
+#[test]
+fn crop_fill_paste() {
+    let mut univ = Universe::from_str("OO.\n..O\nO.O").unwrap();
+    let part = univ.crop((1, 1), Area::new(5, 5));
+    assert_eq!(part.area, Area::new(2, 2));
+    assert_eq!(part.cells, Universe::from_str(".O\n.O").unwrap().cells);
+
+    univ.fill((0, 0), Area::new(2, 2), Cell::Dead);
+    assert_eq!(
+        univ.cells,
+        Universe::from_str("...\n..O\nO.O").unwrap().cells
+    );
+    univ.paste((1, 2), &Universe::from_str("O.\nOO").unwrap());
+    assert_eq!(
+        univ.cells,
+        Universe::from_str("...\n..O\nO.O").unwrap().cells
+    );
+    univ.paste((0, 0), &part);
+    assert_eq!(
+        univ.cells,
+        Universe::from_str(".O.\n.OO\nO.O").unwrap().cells
+    );
+}
+
+#[test]
+fn selection() {
+    let press = |app: &mut App, keys: &str| {
+        for ch in keys.chars() {
+            app.handle_key(KeyEvent::from(KeyCode::Char(ch)));
+        }
+    };
+    let mut app = App::default().with_universes(vec![shapes::empty(Area::new(4, 4))]);
+    app.set_area(Area::new(4, 4));
+    press(&mut app, "e");
+    assert!(app.paused());
+    assert_eq!(app.editor.as_ref().unwrap().cursor, (2, 2));
+
+    // a 2×1 selection, filled, then cut
+    press(&mut app, "kkhvlf");
+    let editor = app.editor.as_ref().unwrap();
+    assert_eq!(editor.selection(), Some(((0, 1), Area::new(2, 1))));
+    assert_eq!(app.universe.population(), 2);
+    press(&mut app, "x");
+    assert_eq!(app.universe.population(), 0);
+    assert_eq!(app.clipboard.as_ref().unwrap().area, Area::new(2, 1));
+
+    // pasted rotated: vertically
+    press(&mut app, "qjp>");
+    assert!(app.editor.as_ref().unwrap().anchor.is_none());
+    press(&mut app, "p");
+    assert_eq!(
+        app.universe.cells,
+        Universe::from_str("....\n..O.\n..O.\n....").unwrap().cells
+    );
+
+    press(&mut app, " q");
+    assert_eq!(app.universe.population(), 1);
+    assert!(app.editor.is_none());
+}
+
+#[test]
+fn clipboard() {
+    assert_eq!(clipboard::base64(b""), "");
+    assert_eq!(clipboard::base64(b"f"), "Zg==");
+    assert_eq!(clipboard::base64(b"fo"), "Zm8=");
+    assert_eq!(clipboard::base64(b"foobar"), "Zm9vYmFy");
+    assert_eq!(clipboard::osc52("O"), "\x1b]52;c;Tw==\x07");
+
+    let mut app = App::default().with_universes(vec![shapes::empty(Area::new(6, 6))]);
+    app.set_area(Area::new(6, 6));
+    app.paste_text("x = 3, y = 1\n3o!");
+    assert!(app.paused());
+    app.handle_key(KeyEvent::from(KeyCode::Enter));
+    assert_eq!(app.universe.population(), 3);
+
+    app.handle_key(KeyEvent::from(KeyCode::Esc));
+    app.handle_key(KeyEvent::from(KeyCode::Char('Y')));
+    let rle = format::to_rle(&Universe::from_str("OOO").unwrap());
+    assert_eq!(app.output, Some(clipboard::osc52(&rle)));
+
+    app.paste_text("not a pattern");
+    assert!(app.message.unwrap().starts_with("can't paste"));
+}
+
+#[test]
+fn undo_redo() {
+    let mut app = App::default().with_universes(vec![shapes::empty(Area::new(4, 4))]);
+    app.set_area(Area::new(4, 4));
+    for code in [KeyCode::Char('e'), KeyCode::Char(' '), KeyCode::Char('l')] {
+        app.handle_key(KeyEvent::from(code));
+    }
+    app.handle_key(KeyEvent::from(KeyCode::Char(' ')));
+    assert_eq!(app.universe.population(), 2);
+    app.handle_key(KeyEvent::from(KeyCode::Char('u')));
+    assert_eq!(app.universe.population(), 1);
+    // ticks in between aren't undone
+    app.tick();
+    assert_eq!(app.universe.population(), 0);
+    app.handle_key(KeyEvent::new(
+        KeyCode::Char('r'),
+        crossterm::event::KeyModifiers::CONTROL,
+    ));
+    assert_eq!(app.universe.population(), 1);
+    assert_eq!(app.universe[(2u16, 3u16)], Cell::Alive);
+    app.handle_key(KeyEvent::from(KeyCode::Char('u')));
+    app.handle_key(KeyEvent::from(KeyCode::Char('u')));
+    app.handle_key(KeyEvent::from(KeyCode::Char('u')));
+    assert_eq!(app.message.as_deref(), Some("nothing to undo"));
+
+    let mut history = History::new(2 * std::mem::size_of::<(usize, Cell, Cell)>());
+    let before = shapes::empty(Area::new(2, 2));
+    let mut univ = before.clone();
+    univ.fill((0, 0), Area::new(2, 1), Cell::Alive);
+    history.record(&before, &univ);
+    let after = shapes::full(Area::new(2, 2));
+    history.record(&univ, &after);
+    // the first edit didn't fit anymore
+    assert_eq!(
+        history.size(),
+        2 * std::mem::size_of::<(usize, Cell, Cell)>()
+    );
+    univ = after;
+    assert!(history.undo(&mut univ));
+    assert!(!history.undo(&mut univ));
+}
+
+#[test]
+fn edit_empty() {
+    // as `:size 0x0` did with an empty pattern
+    let mut app = App::default()
+        .with_universes(vec![shapes::empty(Area::default())])
+        .with_size(Some(Area::default()));
+    app.set_area(Area::new(8, 8));
+    assert!(app.universe.area.is_empty());
+    app.handle_key(KeyEvent::from(KeyCode::Char('e')));
+    assert!(app.editor.is_none());
+    app.handle_key(KeyEvent::from(KeyCode::Char(' ')));
+    app.paste_text("o!");
+    assert!(app.editor.is_none());
+}
+
+#[test]
+fn stamp() {
+    let block = Universe::from_str("OO\nOO").unwrap().with_name("block");
+    let board = shapes::empty(Area::new(8, 8)).with_name("board");
+    let mut app = App::default().with_universes(vec![board, block]);
+    app.set_area(Area::new(8, 8));
+    app.toggle_editor();
+    app.handle_key(KeyEvent::from(KeyCode::Char(' ')));
+    assert_eq!(app.universe.population(), 1);
+
+    app.toggle_browser();
+    app.browser.as_mut().unwrap().select_next();
+    app.handle_key(KeyEvent::from(KeyCode::Char('s')));
+    assert!(app.browser.is_none());
+    // placed twice, the second time beside the board's cell
+    let h = KeyCode::Char('h');
+    for code in [KeyCode::Enter, h, h, h, KeyCode::Enter] {
+        app.handle_key(KeyEvent::from(code));
+    }
+    assert_eq!(app.universe.population(), 8);
+    assert_eq!(app.universe[(4u16, 4u16)], Cell::Alive);
+    assert_eq!(app.universe.name(), "board");
+    app.handle_key(KeyEvent::from(KeyCode::Esc));
+    assert!(app.editor.as_ref().unwrap().pasting.is_none());
+}
+
+#[test]
+fn renderers() {
+    let blinker = Universe::from_str("OOO").unwrap().with_name("blinker");
+    let mut app = App::default()
+        .with_universes(vec![blinker])
+        .with_paused(true);
+    // 16×5 characters inside the border
+    let rendered = |app: &mut App| {
+        let buf = draw(app, 18, 8);
+        (1..6)
+            .map(|y| (1..17).map(|x| buf[(x, y)].symbol()).collect::<String>())
+            .collect::<Vec<_>>()
+    };
+
+    rendered(&mut app);
+    assert_eq!(app.area, Area::new(32, 20));
+    app.handle_key(KeyEvent::from(KeyCode::Char('g')));
+    assert_eq!(app.renderer, Renderer::HalfBlock);
+    let lines = rendered(&mut app);
+    assert_eq!(app.area, Area::new(16, 10));
+    assert_eq!(lines[2], "      ▀▀▀       ");
+    // upright: the top cell on an odd row, alone in its character
+    app.tick();
+    let lines = rendered(&mut app);
+    assert_eq!(lines[1], "       ▄        ");
+    assert_eq!(lines[2], "       ▀        ");
+    let buf = draw(&mut app, 18, 8);
+    let alive = app.theme.alive;
+    let (lower, both) = (&buf[(8, 2)], &buf[(8, 3)]);
+    assert_eq!((lower.fg, lower.bg), (alive, app.theme.background));
+    assert_eq!((both.fg, both.bg), (alive, alive));
+    app.restart();
+
+    app.renderer = Renderer::Block;
+    let lines = rendered(&mut app);
+    assert_eq!(app.area, Area::new(8, 5));
+    assert_eq!(lines[2], "    ██████      ");
+
+    app.renderer = Renderer::Ascii;
+    let lines = rendered(&mut app);
+    assert_eq!(app.area, Area::new(16, 5));
+    assert_eq!(lines[2], "......OOO.......");
+    assert_eq!(Renderer::Ascii.next(), Renderer::Braille);
+    assert_eq!(
+        Renderer::from_str("HalfBlock").unwrap(),
+        Renderer::HalfBlock
+    );
+}
+
+#[test]
+fn ages() {
+    let mut blinker =
+        Universe::from_figur(Area::new(5, 5), Universe::from_str("OOO").unwrap()).unwrap();
+    assert_eq!(blinker.age((2u16, 2u16)), None);
+    let first = blinker.clone();
+    blinker.tick();
+    // still the same cells, ages don't matter
+    blinker.tick();
+    assert_eq!(blinker, first);
+    assert_eq!(blinker.age((2u16, 2u16)), Some(2));
+    assert_eq!(blinker.age((2u16, 1u16)), Some(0));
+    assert_eq!(blinker.age((1u16, 2u16)), Some(0));
+    assert_eq!(blinker.age((0u16, 0u16)), Some(2));
+
+    let theme = Theme::default();
+    let view = blinker.view((0, 0), blinker.area).with_colors(theme, true);
+    assert_eq!(view.color((2, 2)), Some(theme.alive));
+    assert_eq!(view.color((2, 1)), Some(theme.born));
+    assert_eq!(view.color((1, 2)), Some(theme.dying));
+    assert_eq!(view.color((0, 0)), None);
+    let view = blinker.view((0, 0), blinker.area);
+    assert_eq!(view.color((1, 2)), None);
+    assert_eq!(theme.color(Cell::Alive, Some(theme::OLD)), Some(theme.old));
+
+    assert_eq!(Theme::from_str("Light").unwrap().next().next(), theme);
+}
+
+#[test]
+fn heat_map() {
+    let mut app = App::default().with_universes(vec![Universe::from_str("OOO").unwrap()]);
+    app.set_area(Area::new(5, 5));
+    app.handle_key(KeyEvent::from(KeyCode::Char('m')));
+    for _ in 0..4 {
+        app.tick();
+    }
+    let heat = app.heat.as_ref().unwrap();
+    let idx = |row: usize, col: usize| row * 5 + col;
+    // the blinker's ends change every generation, the middle never
+    assert_eq!(heat.heat(idx(2, 1)), 4. / HEAT_GENERATIONS as f64);
+    assert_eq!(heat.heat(idx(1, 2)), 4. / HEAT_GENERATIONS as f64);
+    assert_eq!(heat.heat(idx(2, 2)), 0.);
+    let view = app.universe.view((0, 0), app.area).with_heat(Some(heat));
+    // live cells are tinted by their heat too
+    let alive = Theme::default().alive;
+    assert!(view.color((1, 2)).is_some_and(|color| color != alive));
+    assert_eq!(view.color((2, 2)), Some(alive));
+    assert!(view.color((2, 1)).is_some());
+    assert_eq!(view.color((0, 0)), None);
+
+    let mut window = HeatMap::new(2);
+    let mut blinker = app.universe.clone();
+    for _ in 0..5 {
+        blinker.tick();
+        window.record(&blinker);
+    }
+    assert_eq!(window.heat(idx(2, 1)), 1.);
+    assert_eq!(
+        HeatMap::color(1.),
+        Some(ratatui::style::Color::Rgb(255, 230, 60))
+    );
+    assert_eq!(
+        HeatMap::tint(ratatui::style::Color::Black, 1.),
+        ratatui::style::Color::Rgb(127, 115, 30)
+    );
+    assert_eq!(
+        HeatMap::tint(ratatui::style::Color::Black, 0.),
+        ratatui::style::Color::Black
+    );
+
+    app.handle_key(KeyEvent::from(KeyCode::Char('M')));
+    assert_eq!(app.heat.as_ref().unwrap().heat(idx(2, 1)), 0.);
+}
+
+#[test]
+fn help() {
+    let mut app = App::default().with_paused(true);
+    app.handle_key(KeyEvent::from(KeyCode::Char('?')));
+    assert!(app.help);
+    let screen = screen(&mut app, 100, 40);
+    for (keys, action) in app.keymap.help(Mode::Normal) {
+        assert!(screen.contains(action.help()), "{action:?}");
+        assert!(screen.contains(&keys[0].to_string()), "{action:?}");
+    }
+    assert!(screen.contains("Keys: normal"));
+    // any key closes it, without doing anything else
+    app.handle_key(KeyEvent::from(KeyCode::Char('q')));
+    assert!(!app.help);
+
+    let keymap = Keymap::default();
+    let normal = keymap.help(Mode::Normal);
+    let quit = normal.iter().find(|(_, a)| *a == Action::Quit).unwrap();
+    assert_eq!(
+        quit.0.iter().map(ToString::to_string).collect::<Vec<_>>(),
+        ["q", "esc"]
+    );
+    let ctrl_r = KeyEvent::new(KeyCode::Char('r'), crossterm::event::KeyModifiers::CONTROL);
+    assert_eq!(keymap.action(Mode::Edit, ctrl_r.into()), Some(Action::Redo));
+    assert_eq!(keymap.action(Mode::Browser, keys::Key::ch('u')), None);
+}
+
+#[test]
+fn key_hints() {
+    use keys::Key;
+    let mut keymap = Keymap::default();
+    keymap
+        .bind(Mode::Normal, Action::Quit, &[Key::ch('x')])
+        .unwrap();
+    keymap
+        .bind(Mode::Edit, Action::Select, &[Key::new(KeyCode::Tab)])
+        .unwrap();
+    assert_eq!(keymap.keys_for(Mode::Normal, Action::Quit), [Key::ch('x')]);
+    assert_eq!(
+        keymap.hint(Mode::Normal, &[Action::ScrollLeft, Action::ScrollRight]),
+        Some("[HL]".into())
+    );
+    let mut app = App::default().with_keymap(keymap);
+    let normal = screen(&mut app, 120, 10);
+    assert!(
+        normal.contains("[x] quit, [r] restart, [space] pause"),
+        "{normal}"
+    );
+    assert!(!normal.contains("[q]"), "{normal}");
+
+    app.toggle_editor();
+    let edit = screen(&mut app, 120, 10);
+    assert!(edit.contains("edit: [tab] select, [y] yank"), "{edit}");
+    app.copy();
+    assert_eq!(
+        app.message.as_deref(),
+        Some("nothing selected: [tab] to select")
+    );
+}
+
+#[test]
+fn config_keys() {
+    use config::Config;
+    use keys::Key;
+    let ctrl_c = KeyEvent::new(KeyCode::Char('c'), crossterm::event::KeyModifiers::CONTROL);
+    assert_eq!("ctrl-c".parse::<Key>().unwrap(), ctrl_c.into());
+    for key in [
+        "q",
+        "-",
+        "ctrl--",
+        "alt-enter",
+        "space",
+        "pagedown",
+        "f5",
+        "ctrl-alt-x",
+    ] {
+        assert_eq!(key.parse::<Key>().unwrap().to_string(), key);
+    }
+    assert_eq!("Esc".parse::<Key>().unwrap(), Key::new(KeyCode::Esc));
+    for key in ["", "shift-a", "f13", "nope", "ctrl-"] {
+        assert!(key.parse::<Key>().is_err(), "{key}");
+    }
+    for action in Action::ALL {
+        assert_eq!(action.name().parse::<Action>().unwrap(), action);
+    }
+    assert_eq!("play-pause".parse::<Action>().unwrap(), Action::PlayPause);
+
+    let path = std::path::Path::new("config.toml");
+    let text = r#"
+        [keys.normal]
+        quit = ["x", "ctrl-c"]
+        faster = "+"
+        # taken from the previous pattern
+        restart = "p"
+        [keys.edit]
+        back = []
+    "#;
+    let keymap = Config::parse(path, text).unwrap().keymap;
+    assert_eq!(
+        keymap.action(Mode::Normal, ctrl_c.into()),
+        Some(Action::Quit)
+    );
+    assert_eq!(
+        keymap.action(Mode::Normal, Key::ch('x')),
+        Some(Action::Quit)
+    );
+    assert_eq!(keymap.action(Mode::Normal, Key::ch('q')), None);
+    assert_eq!(
+        keymap.action(Mode::Normal, Key::ch('+')),
+        Some(Action::Faster)
+    );
+    assert_eq!(keymap.action(Mode::Normal, Key::ch('k')), None);
+    assert_eq!(
+        keymap.action(Mode::Normal, Key::ch('p')),
+        Some(Action::Restart)
+    );
+    assert_eq!(
+        keymap.action(Mode::Normal, Key::ch('h')),
+        Some(Action::PrevPattern)
+    );
+    assert_eq!(keymap.action(Mode::Edit, Key::ch('q')), None);
+    // other modes untouched
+    assert_eq!(keymap.action(Mode::Paste, Key::ch('q')), Some(Action::Back));
+    // in the place of the old keys in the help
+    assert_eq!(keymap.help(Mode::Normal)[0].1, Action::Quit);
+
+    let mut app = App::default().with_keymap(keymap);
+    assert!(!app.handle_key(ctrl_c));
+
+    let errors = [
+        (
+            "[keys.normal]\nquit = \"ctrl-\"",
+            "keys.normal.quit: invalid key",
+        ),
+        (
+            "[keys.normal]\nfly = \"f\"",
+            "keys.normal.fly: invalid action",
+        ),
+        ("[keys.insert]\nquit = \"q\"", "keys.insert: invalid mode"),
+        (
+            "[keys.browser]\nundo = \"u\"",
+            "not available in browser mode",
+        ),
+        (
+            "[keys.normal]\nquit = \"x\"\nfaster = \"x\"",
+            "x is bound to",
+        ),
+        (
+            "[keys.normal]\nquit = 1",
+            "should be a key or a list of keys",
+        ),
+        ("speeed = 1", "speeed: unknown setting"),
+        ("[keys", "config.toml: "),
+    ];
+    for (text, error) in errors {
+        let e = Config::parse(path, text).unwrap_err().to_string();
+        assert!(e.contains(error), "{e}");
+    }
+}
+
+#[test]
+fn config_settings() {
+    use config::Config;
+    let path = std::path::Path::new("/etc/cgol/config.toml");
+    let text = r#"
+        rule = "B36/S23"
+        speed = 10
+        renderer = "halfblock"
+        theme = "light"
+        topology = "plane"
+        library = ["patterns", "/usr/share/patterns"]
+        pattern = "Acorn"
+    "#;
+    let config = Config::parse(path, text).unwrap();
+    assert_eq!(config.rule, Some("B36/S23".parse().unwrap()));
+    assert_eq!(config.speed, Some(10.));
+    assert_eq!(config.renderer, Some(Renderer::HalfBlock));
+    assert_eq!(config.theme, Some(Theme::ALL[1]));
+    assert_eq!(config.topology, Some(Topology::Plane));
+    assert_eq!(
+        config.library,
+        ["/etc/cgol/patterns", "/usr/share/patterns"].map(std::path::PathBuf::from)
+    );
+    assert_eq!(config.keymap, Keymap::default());
+    assert_eq!(Config::parse(path, "speed = 0.5").unwrap().speed, Some(0.5));
+    assert_eq!(Config::parse(path, "").unwrap(), Config::default());
+
+    let app = App::default()
+        .with_pattern(config.pattern.as_deref().unwrap())
+        .unwrap();
+    assert_eq!(app.get_nth(app.i).name(), "Acorn");
+    let app = App::default().with_pattern("random").unwrap();
+    assert_eq!(app.i, app.len() - shapes::N + 2);
+    assert!(App::default().with_pattern("nothing").is_err());
+
+    let errors = [
+        ("rule = \"B9\"", "rule: invalid rule"),
+        ("speed = -1", "speed: should be a positive number"),
+        ("speed = \"fast\"", "speed: should be a positive number"),
+        ("theme = \"pink\"", "theme: invalid theme"),
+        ("renderer = 3", "renderer: should be a string"),
+        (
+            "library = \"patterns\"",
+            "library: should be a list of paths",
+        ),
+        ("pattern = 1", "pattern: should be a pattern name"),
+    ];
+    for (text, error) in errors {
+        let e = Config::parse(path, text).unwrap_err().to_string();
+        assert!(e.contains(error), "{e}");
+    }
+}
+
+#[test]
+fn command_line() {
+    let type_in = |app: &mut App, text: &str| {
+        for ch in text.chars() {
+            app.handle_key(KeyEvent::from(KeyCode::Char(ch)));
+        }
+    };
+    assert_eq!(
+        "rule B36/S23".parse::<Command>().unwrap(),
+        Command::Rule("B36/S23".parse().unwrap())
+    );
+    assert_eq!(
+        " size  20x10 ".parse::<Command>().unwrap(),
+        Command::Size(Some(Area::new(20, 10)))
+    );
+    assert_eq!("size auto".parse::<Command>().unwrap(), Command::Size(None));
+    for (command, error) in [
+        ("size 0x0", "invalid size: \"0x0\""),
+        ("size 20x0", "invalid size: \"20x0\""),
+        ("fly", "invalid command: \"fly\""),
+        ("goto", "goto: missing <GENERATION>"),
+        ("goto soon", "invalid generation: \"soon\""),
+        ("rule B9", "invalid rule"),
+    ] {
+        let e = command.parse::<Command>().unwrap_err().to_string();
+        assert!(e.contains(error), "{e}");
+    }
+
+    let mut app = App::default()
+        .with_paused(true)
+        .with_pattern("glider")
+        .unwrap();
+    app.set_area(Area::new(20, 10));
+    type_in(&mut app, ":goto 8");
+    assert!(app.command_line.active);
+    assert_eq!(app.command_line.input, "goto 8");
+    app.handle_key(KeyEvent::from(KeyCode::Enter));
+    assert!(!app.command_line.active);
+    assert_eq!(app.target, Some(8));
+    app.fast_forward(Duration::MAX);
+    assert_eq!((app.generation, app.target), (8, None));
+    let mut glider = App::default().with_pattern("glider").unwrap();
+    glider.set_area(Area::new(20, 10));
+    glider.tick_n(8, Duration::MAX);
+    assert_eq!(app.universe, glider.universe);
+    // back in time: from the start
+    app.execute("goto 2");
+    assert_eq!(app.generation, 0);
+    app.fast_forward(Duration::MAX);
+    assert_eq!(app.generation, 2);
+
+    app.execute("rule B36/S23");
+    assert_eq!(app.universe.rule, "B36/S23".parse().unwrap());
+    assert_eq!(app.generation, 2);
+    app.execute("size 30x12");
+    assert_eq!(app.universe.area, Area::new(30, 12));
+    assert_eq!(app.generation, 0);
+    app.execute("sizes");
+    assert_eq!(app.message.as_deref(), Some("invalid command: \"sizes\""));
+
+    // history
+    type_in(&mut app, ":");
+    app.handle_key(KeyEvent::from(KeyCode::Up));
+    assert_eq!(app.command_line.input, "goto 8");
+    app.handle_key(KeyEvent::from(KeyCode::Down));
+    assert_eq!(app.command_line.input, "");
+    // completion
+    type_in(&mut app, "s");
+    app.handle_key(KeyEvent::from(KeyCode::Tab));
+    assert_eq!(app.command_line.hints, ["save", "seed", "size"]);
+    type_in(&mut app, "i");
+    app.handle_key(KeyEvent::from(KeyCode::Tab));
+    assert_eq!(app.command_line.input, "size ");
+    type_in(&mut app, "a");
+    app.handle_key(KeyEvent::from(KeyCode::Tab));
+    assert_eq!(app.command_line.input, "size auto");
+    app.handle_key(KeyEvent::from(KeyCode::Esc));
+    assert!(!app.command_line.active);
+
+    let dir = TempDir::new("command");
+    std::fs::create_dir_all(dir.join("more")).unwrap();
+    let saved = dir.join("glider.rle");
+    app.execute("size auto");
+    app.execute(&format!("save {}", saved.display()));
+    assert_eq!(
+        app.message,
+        Some(format!("saved 3×3 to {}", saved.display()))
+    );
+    type_in(&mut app, &format!(":load {}/", dir.display()));
+    app.handle_key(KeyEvent::from(KeyCode::Tab));
+    assert_eq!(app.command_line.hints, ["glider.rle", "more/"]);
+    type_in(&mut app, "g");
+    app.handle_key(KeyEvent::from(KeyCode::Tab));
+    assert_eq!(app.command_line.input, format!("load {}", saved.display()));
+    app.handle_key(KeyEvent::from(KeyCode::Enter));
+    assert_eq!(app.message.as_deref(), Some("loaded Glider"));
+    assert_eq!(app.i, app.available_universes.len() - 1);
+    assert_eq!(app.universe.trim().cells, glider.get_nth(glider.i).cells);
+}
+
+#[test]
+fn jump() {
+    let mut app = App::default()
+        .with_pattern("acorn")
+        .unwrap()
+        .with_generation(Some(300));
+    // the start doesn't cancel it
+    app.set_area(Area::new(80, 40));
+    assert_eq!(app.target, Some(300));
+    app.fast_forward(Duration::ZERO);
+    assert_eq!((app.generation, app.target), (0, Some(300)));
+
+    // the size of the terminal restarts it
+    draw(&mut app, 40, 12);
+    app.tick_n(150, Duration::MAX);
+    let screen = screen(&mut app, 40, 12);
+    assert!(screen.contains("generation 150 of 300"), "{screen}");
+    assert!(screen.contains("[q] cancel"), "{screen}");
+
+    // esc cancels instead of quitting
+    assert!(app.handle_key(KeyEvent::from(KeyCode::Esc)));
+    assert_eq!(app.target, None);
+    assert_eq!(app.generation, 150);
+    assert_eq!(
+        app.message.as_deref(),
+        Some("stopped at generation 150 of 300")
+    );
+
+    app.handle_key(KeyEvent::from(KeyCode::Char('G')));
+    assert_eq!(app.command_line.input, "goto ");
+    for ch in "200".chars() {
+        app.handle_key(KeyEvent::from(KeyCode::Char(ch)));
+    }
+    app.handle_key(KeyEvent::from(KeyCode::Enter));
+    app.fast_forward(Duration::MAX);
+    assert_eq!((app.generation, app.target), (200, None));
+    let mut acorn = App::default().with_pattern("acorn").unwrap();
+    acorn.set_area(app.area);
+    acorn.tick_n(200, Duration::MAX);
+    assert_eq!(app.universe, acorn.universe);
+}
+
+#[test]
+fn bookmarks() {
+    let key = |app: &mut App, ch: char| {
+        app.handle_key(KeyEvent::from(KeyCode::Char(ch)));
+    };
+    let mut app = App::default()
+        .with_paused(true)
+        .with_pattern("glider")
+        .unwrap();
+    app.set_area(Area::new(20, 10));
+    app.tick_n(4, Duration::MAX);
+    key(&mut app, 's');
+    assert_eq!(app.message.as_deref(), Some("bookmarked generation 4"));
+    let at_4 = app.universe.clone();
+    app.tick_n(8, Duration::MAX);
+    key(&mut app, 's');
+    app.tick_n(8, Duration::MAX);
+    let at_20 = app.universe.clone();
+
+    key(&mut app, '\'');
+    assert_eq!(app.mode(), Mode::Bookmarks);
+    // the latest one is selected
+    assert_eq!(app.bookmarks.selected().unwrap().generation, 12);
+    key(&mut app, 'k');
+    app.handle_key(KeyEvent::from(KeyCode::Enter));
+    assert_eq!(app.generation, 4);
+    assert_eq!(app.universe, at_4);
+    // going back can be undone
+    app.undo();
+    assert_eq!(app.universe.cells, at_20.cells);
+    app.redo();
+    assert_eq!(app.universe.cells, at_4.cells);
+    app.tick();
+    assert_eq!(app.generation, 5);
+
+    let screen = screen(&mut app, 80, 12);
+    assert!(screen.contains("Bookmarks"));
+    assert!(screen.contains("gen 12 20×10, 5 alive"), "{screen}");
+
+    let (pattern, comments) = app.bookmarks.to_pattern();
+    assert_eq!(pattern.area, Area::new(3 + 4 + 3, 3));
+    assert_eq!(
+        comments,
+        [
+            "generation 4 at column 0: 3×3, 5 alive",
+            "generation 12 at column 7: 3×3, 5 alive"
+        ]
+    );
+    let dir = TempDir::new("bookmarks");
+    for file in ["marks.rle", "marks.cells", "marks.lif"] {
+        let path = dir.join(file);
+        app.execute(&format!("bookmarks {}", path.display()));
+        assert_eq!(
+            app.message,
+            Some(format!("saved 2 bookmarks to {}", path.display()))
+        );
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert!(text.contains("generation 12 at column 7"), "{text}");
+        let loaded = load::load(&path).remove(0).unwrap();
+        assert_eq!(loaded.trim().cells, pattern.cells, "{file}");
+    }
+
+    key(&mut app, 'd');
+    key(&mut app, 'd');
+    assert!(app.bookmarks.list.is_empty());
+    assert_eq!(app.bookmarks.selected(), None);
+    key(&mut app, 'w');
+    app.handle_key(KeyEvent::from(KeyCode::Char('x')));
+    app.handle_key(KeyEvent::from(KeyCode::Enter));
+    assert_eq!(app.message.as_deref(), Some("no bookmarks to save"));
+    key(&mut app, 'q');
+    assert_eq!(app.mode(), Mode::Normal);
+}
+
+#[test]
+fn record() {
+    assert_eq!(record::parse_color("#ff8800").unwrap(), [255, 136, 0]);
+    assert_eq!(record::parse_color("0A0b0C").unwrap(), [10, 11, 12]);
+    for bad in ["", "fff", "#gg0000", "ff88001"] {
+        assert!(record::parse_color(bad).is_err(), "{bad}");
+    }
+    let style = record::Style {
+        cell: 2,
+        alive: [255, 136, 0],
+        dead: [0, 0, 64],
+    };
+    assert!(record::Recorder::new(vec![], Area::new(0, 5), style).is_err());
+
+    let glider = Universe::from_str(shapes::GLIDER).unwrap();
+    let mut recorder = record::Recorder::new(vec![], Area::new(5, 4), style).unwrap();
+    recorder.frame(&glider, 10).unwrap();
+    let gif = recorder.finish().unwrap();
+    let mut decoder = gif::DecodeOptions::new();
+    decoder.set_color_output(gif::ColorOutput::RGBA);
+    let mut decoder = decoder.read_info(gif.as_slice()).unwrap();
+    assert_eq!((decoder.width(), decoder.height()), (10, 8));
+    let frame = decoder.read_next_frame().unwrap().unwrap();
+    assert_eq!(frame.delay, 10);
+    let pixel = |row: usize, col: usize| {
+        let i = (row * 10 + col) * 4;
+        frame.buffer[i..i + 3].to_vec()
+    };
+    for row in 0..8 {
+        for col in 0..10 {
+            let (r, c) = (row as u16 / 2, col as u16 / 2);
+            let alive = r < 3 && c < 3 && glider[(r, c)] == Cell::Alive;
+            let color = if alive { style.alive } else { style.dead };
+            assert_eq!(pixel(row, col), color, "{row}, {col}");
+        }
+    }
+
+    let dir = TempDir::new("record");
+    let path = dir.join("glider.gif");
+    let mut app = App::default()
+        .with_paused(true)
+        .with_pattern("glider")
+        .unwrap();
+    app.set_area(Area::new(20, 10));
+    app.execute(&format!("record {}", path.display()));
+    assert_eq!(
+        app.message,
+        Some(format!("recording to {}", path.display()))
+    );
+    // one frame per frame drawn, shown as long as it was on screen
+    std::thread::sleep(Duration::from_millis(50));
+    app.tick_n(5, Duration::MAX);
+    app.record_frame();
+    app.record_frame();
+    // replaced by the next one if drawn too briefly
+    app.tick();
+    app.record_frame();
+    std::thread::sleep(Duration::from_millis(30));
+    // jumped over generations aren't recorded
+    app.goto(20);
+    app.record_frame();
+    app.fast_forward(Duration::MAX);
+    app.record_frame();
+    app.handle_key(KeyEvent::from(KeyCode::Char('w')));
+    assert_eq!(
+        app.message,
+        Some(format!("recorded 3 generations to {}", path.display()))
+    );
+    let file = std::fs::File::open(&path).unwrap();
+    let mut decoder = gif::DecodeOptions::new().read_info(file).unwrap();
+    assert_eq!((decoder.width(), decoder.height()), (20 * 4, 10 * 4));
+    let mut delays = vec![];
+    while let Some(frame) = decoder.read_next_frame().unwrap() {
+        delays.push(frame.delay);
+    }
+    assert_eq!(delays.len(), 3);
+    assert!(
+        delays[0] >= 5 && delays[1] >= 3 && delays[2] >= 2,
+        "{delays:?}"
+    );
+}
@@ -0,0 +1,112 @@
+use super::{Cell, Error};
+use ratatui::style::Color;
+
+/// Generations after which a live cell counts as long-lived
+pub const OLD: u16 = 20;
+
+/// Colours of the ui
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    pub name: &'static str,
+    /// live cells
+    pub alive: Color,
+    /// cells born in the last generation, when colouring by age
+    pub born: Color,
+    /// cells alive for at least `OLD` generations, when colouring by age
+    pub old: Color,
+    /// cells died in the last generation, when colouring by age
+    pub dying: Color,
+    /// behind the cells
+    pub background: Color,
+    pub border: Color,
+    /// key hints in the footer
+    pub keys: Color,
+    /// speed and other information in the footer
+    pub info: Color,
+    /// messages, eg. what went wrong
+    pub error: Color,
+}
+impl Default for Theme {
+    fn default() -> Self {
+        Self::ALL[0]
+    }
+}
+impl Theme {
+    pub const ALL: [Theme; 3] = [
+        Theme {
+            name: "dark",
+            alive: Color::White,
+            born: Color::LightGreen,
+            old: Color::LightBlue,
+            dying: Color::Red,
+            background: Color::Reset,
+            border: Color::Reset,
+            keys: Color::Yellow,
+            info: Color::LightBlue,
+            error: Color::LightRed,
+        },
+        Theme {
+            name: "light",
+            alive: Color::Black,
+            born: Color::Green,
+            old: Color::Blue,
+            dying: Color::LightRed,
+            background: Color::Reset,
+            border: Color::DarkGray,
+            keys: Color::Magenta,
+            info: Color::Blue,
+            error: Color::Red,
+        },
+        Theme {
+            name: "matrix",
+            alive: Color::Green,
+            born: Color::LightGreen,
+            old: Color::Rgb(0, 110, 0),
+            dying: Color::Rgb(0, 50, 0),
+            background: Color::Black,
+            border: Color::Green,
+            keys: Color::LightGreen,
+            info: Color::Green,
+            error: Color::LightRed,
+        },
+    ];
+
+    /// Colour of `cell`, `None` if it's not drawn.
+    /// `age`: generations it's been in this state, if colouring by age and it's known
+    pub fn color(&self, cell: Cell, age: Option<u16>) -> Option<Color> {
+        match (cell, age) {
+            (Cell::Alive, Some(0)) => Some(self.born),
+            (Cell::Alive, Some(age)) if age >= OLD => Some(self.old),
+            (Cell::Alive, _) => Some(self.alive),
+            (Cell::Dead, Some(0)) => Some(self.dying),
+            (Cell::Dead, _) => None,
+        }
+    }
+    /// The one after this one, wrapping around
+    #[must_use]
+    pub fn next(self) -> Self {
+        let i = Self::ALL
+            .iter()
+            .position(|t| t.name == self.name)
+            .unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+}
+impl std::fmt::Display for Theme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+impl std::str::FromStr for Theme {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|t| t.name == s.to_lowercase())
+            .ok_or_else(|| Error::Invalid {
+                what: "theme",
+                value: s.to_string(),
+            })
+    }
+}
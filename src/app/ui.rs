@@ -1,16 +1,20 @@
-use crate::{app::App, app::Area};
+use crate::app::{
+    render::{Grid, Overlay},
+    Action, App, Area, Bookmarks, Browser, CommandLine, Editor, Keymap, Mode, Orientation,
+    Renderer, Theme,
+};
 use ratatui::{
-    layout::{Constraint, Layout},
+    layout::{Constraint, Layout, Rect},
     style::Stylize,
     text::Line,
-    widgets::{canvas::Canvas, Block, BorderType},
+    widgets::{canvas::Canvas, Block, BorderType, Clear, Gauge, List, ListItem, Paragraph, Wrap},
     Frame,
 };
 
-/// area of a braille character
-const BRAILLE: Area = Area {
-    width: 2,
-    height: 4,
+/// smallest terminal the ui fits into
+const MIN_SIZE: Area = Area {
+    width: 16,
+    height: 5,
 };
 
 /// ```text
@@ -22,45 +26,464 @@ const BRAILLE: Area = Area {
 /// |____________________|
 /// ```
 pub fn ui(f: &mut Frame, app: &mut App) {
+    if f.area().width < MIN_SIZE.width || f.area().height < MIN_SIZE.height {
+        let too_small = Paragraph::new("terminal too small".fg(app.theme.error))
+            .centered()
+            .wrap(Wrap { trim: true });
+        f.render_widget(too_small, f.area());
+        return;
+    }
     let chunks = Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).split(f.area());
 
-    let cgol = Block::bordered()
-        .border_type(BorderType::Rounded)
-        .title(format!(" Conway's Game of Life - {} ", app.universe.name));
     // 2 blocks less: border
-    let new_area = Area::new(
-        (chunks[0].width - 2) * BRAILLE.width,
-        (chunks[0].height - 2) * BRAILLE.height,
-    );
+    let new_area = app
+        .renderer
+        .area(Area::new(chunks[0].width - 2, chunks[0].height - 2));
     // apply the area change
     if app.area != new_area {
-        app.area = new_area;
-        app.restart();
+        app.set_area(new_area);
+    }
+    let theme = app.theme;
+    let mut cgol = Block::bordered()
+        .border_type(BorderType::Rounded)
+        .border_style(theme.border)
+        .title(format!(" Conway's Game of Life - {} ", app.universe.name));
+    if app.orientation != Orientation::default() {
+        cgol = cgol.title(format!("({}) ", app.orientation));
+    }
+    if let Some(recording) = &app.recording {
+        let rec = format!(" ● rec {} ", recording.frames());
+        cgol = cgol.title(Line::from(rec.fg(theme.error)).right_aligned());
+    }
+    if app.scrollable() {
+        let (row, col) = app.offset;
+        let size = app.universe.area;
+        let mut title = format!(" {}×{} at {row};{col}", size.width, size.height);
+        // the cursor scrolls in the editor
+        let (mode, scroll) = match app.mode() {
+            mode @ (Mode::Edit | Mode::Paste) => (mode, FAR),
+            _ => (Mode::Normal, SCROLL),
+        };
+        if let Some(keys) = app.keymap.hint(mode, &scroll) {
+            title += &format!(", scroll: {keys}");
+        }
+        cgol = cgol.title_bottom(title + " ");
+    }
+    if let Some(target) = app.target {
+        // not drawing the generations in between
+        let inner = cgol.inner(chunks[0]);
+        f.render_widget(cgol, chunks[0]);
+        let [_, middle, _] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(1),
+            Constraint::Fill(1),
+        ])
+        .areas(inner);
+        let gauge = Gauge::default()
+            .gauge_style(theme.info)
+            .ratio(app.generation as f64 / target.max(1) as f64)
+            .label(format!("generation {} of {target}", app.generation));
+        f.render_widget(gauge, middle);
+    } else {
+        render_universe(f, app, cgol, chunks[0]);
+    }
+
+    let panel = Layout::horizontal([Constraint::Fill(1), Constraint::Max(40)]).split(chunks[0]);
+    if let Some(browser) = app.browser.as_mut() {
+        render_browser(f, browser, &app.keymap, theme, panel[1]);
+    }
+    if app.bookmarks.panel.is_some() {
+        render_bookmarks(f, &mut app.bookmarks, &app.keymap, theme, panel[1]);
     }
-    let universe = Canvas::default()
-        // .x_bounds([0., chunks[0].height as f64 * 2. - 4.])
-        // .y_bounds([0., chunks[0].height as f64 * 2. - 4.])
-        .paint(|ctx| ctx.draw(&app.universe))
-        .block(cgol);
 
-    f.render_widget(universe, chunks[0]);
+    if app.help {
+        render_help(f, app, chunks[0]);
+    }
 
     let footer = Layout::horizontal([Constraint::Fill(1)]).split(chunks[1]);
 
-    let current_keys_hint = "[q]uit, [r]estart, pause: [ ], nav: vim/arrows".yellow();
+    if app.command_line.active {
+        f.render_widget(command_line(&app.command_line, theme), footer[0]);
+        return;
+    }
 
-    let poll_t = {
-        if let super::PAUSE = app.poll_t {
+    // messages take the place of the footer until the next key press
+    if let Some(message) = &app.message {
+        f.render_widget(Line::from(message.as_str().fg(theme.error)), footer[0]);
+        return;
+    }
+
+    if app.target.is_some() {
+        // quitting or going back cancels it
+        let mode = app.mode();
+        let cancel = (app.keymap.hint(mode, &[Action::Quit]))
+            .or_else(|| app.keymap.hint(mode, &[Action::Back]))
+            .map(|key| format!("{key} cancel"))
+            .unwrap_or_default();
+        let jumping = Line::from(vec!["jumping, ".fg(theme.info), cancel.fg(theme.keys)]);
+        f.render_widget(jumping, footer[0]);
+        return;
+    }
+
+    if let Some(editor) = &app.editor {
+        f.render_widget(editor_footer(editor, &app.keymap, theme), footer[0]);
+        return;
+    }
+
+    let current_keys_hint = hints(
+        &app.keymap,
+        Mode::Normal,
+        &[
+            (&[Action::Quit], "quit"),
+            (&[Action::Restart], "restart"),
+            (&[Action::PlayPause], "pause"),
+            (
+                &[
+                    Action::PrevPattern,
+                    Action::Slower,
+                    Action::Faster,
+                    Action::NextPattern,
+                ],
+                "nav",
+            ),
+            (&[Action::Browse], "browse"),
+            (&[Action::Edit], "edit"),
+            (&[Action::Help], "help"),
+        ],
+    )
+    .fg(theme.keys);
+
+    let speed = {
+        if app.paused() {
             "paused".into()
+        } else if app.speed < 10. {
+            format!("{:.1} gen/s", app.speed)
         } else {
-            format!("Poll time: {:.0?}", app.poll_t)
+            format!("{:.0} gen/s", app.speed)
         }
     }
-    .light_blue();
+    .fg(theme.info);
 
-    let div = " | ".white();
-    let current_stats = vec![current_keys_hint, div, poll_t];
+    let generation = format!("gen {}", app.generation).fg(theme.info);
+    let div = || " | ".fg(theme.border);
+    let current_stats = vec![current_keys_hint, div(), generation, div(), speed];
     let footer_data = Line::from(current_stats);
 
     f.render_widget(footer_data, footer[0]);
 }
+
+/// The visible part of the universe in `cgol`, with the editor on top
+fn render_universe(f: &mut Frame, app: &App, cgol: Block, area: Rect) {
+    let theme = app.theme;
+    let overlay = app.editor.as_ref().map(Editor::overlay).unwrap_or_default();
+    let view = app
+        .universe
+        .view(app.offset, app.area)
+        .with_colors(theme, app.aging)
+        .with_heat(app.heat.as_ref());
+    if app.renderer == Renderer::Braille {
+        let universe = Canvas::default()
+            .background_color(theme.background)
+            .paint(|ctx| {
+                ctx.draw(&view);
+                ctx.layer();
+                ctx.draw(&Overlay {
+                    cells: &overlay,
+                    offset: app.offset,
+                    area: app.area,
+                });
+            })
+            .block(cgol);
+        f.render_widget(universe, area);
+    } else {
+        let grid = Grid {
+            view,
+            renderer: app.renderer,
+            overlay: &overlay,
+        };
+        f.render_widget(grid, cgol.inner(area));
+        f.render_widget(cgol, area);
+    }
+}
+
+/// actions scrolling the view, left, down, up and right
+const SCROLL: [Action; 4] = [
+    Action::ScrollLeft,
+    Action::ScrollDown,
+    Action::ScrollUp,
+    Action::ScrollRight,
+];
+/// actions moving the cursor to the edge of the view, left, down, up and right
+const FAR: [Action; 4] = [
+    Action::FarLeft,
+    Action::FarDown,
+    Action::FarUp,
+    Action::FarRight,
+];
+/// actions orienting a pattern
+const ORIENT: [Action; 5] = [
+    Action::RotateRight,
+    Action::RotateLeft,
+    Action::FlipHorizontal,
+    Action::FlipVertical,
+    Action::Transpose,
+];
+
+/// `[keys] label` of each group of actions of `mode` that is bound, eg. `[q] quit, [hjkl] nav`
+fn hints(keymap: &Keymap, mode: Mode, hints: &[(&[Action], &str)]) -> String {
+    hints
+        .iter()
+        .filter_map(|(actions, label)| Some(format!("{} {label}", keymap.hint(mode, actions)?)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Keys of the current editing mode, position of the cursor and size of the selection
+fn editor_footer(editor: &Editor, keymap: &Keymap, theme: Theme) -> Line<'static> {
+    let keys = match &editor.pasting {
+        Some(paste) => {
+            let (what, back) = if paste.stamp {
+                ("stamp: ", "done")
+            } else {
+                ("paste: ", "cancel")
+            };
+            let hints = hints(
+                keymap,
+                Mode::Paste,
+                &[
+                    (&[Action::Place], "place"),
+                    (&ORIENT, "orient"),
+                    (&[Action::Back], back),
+                    (&[Action::Help], "help"),
+                ],
+            );
+            what.to_owned() + &hints
+        }
+        None => {
+            let hints = hints(
+                keymap,
+                Mode::Edit,
+                &[
+                    (&[Action::Select], "select"),
+                    (&[Action::Copy], "yank"),
+                    (&[Action::Cut], "cut"),
+                    (&[Action::Paste], "paste"),
+                    (&[Action::Clear], "delete"),
+                    (&[Action::Fill], "fill"),
+                    (&[Action::ToggleCell], "toggle"),
+                    (&[Action::Undo], "undo"),
+                    (&[Action::Help], "help"),
+                ],
+            );
+            "edit: ".to_owned() + &hints
+        }
+    };
+    let (row, col) = editor.cursor;
+    let mut position = format!("{row};{col}");
+    if let Some((_, area)) = editor.selection() {
+        position += &format!(", {}×{} selected", area.width, area.height);
+    }
+    Line::from(vec![
+        keys.fg(theme.keys),
+        " | ".fg(theme.border),
+        position.fg(theme.info),
+    ])
+}
+
+/// What's typed, then the options of an ambiguous completion
+fn command_line(line: &CommandLine, theme: Theme) -> Line<'static> {
+    let mut spans = vec![
+        ":".fg(theme.keys),
+        line.input.clone().into(),
+        "_".fg(theme.keys),
+    ];
+    if !line.hints.is_empty() {
+        spans.push("  ".into());
+        spans.push(line.hints.join("  ").fg(theme.info));
+    }
+    Line::from(spans)
+}
+
+/// ```text
+///  _keys: normal_______________
+/// | q, esc  quit  r  restart   |
+/// | space   play  ?  this help |
+/// |____________________________|
+/// ```
+fn render_help(f: &mut Frame, app: &App, area: Rect) {
+    let mode = app.mode();
+    let help = app.keymap.help(mode);
+    let keys = help
+        .iter()
+        .map(|(keys, _)| {
+            keys.iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        })
+        .collect::<Vec<_>>();
+    let key_width = keys.iter().map(|k| k.chars().count()).max().unwrap_or(0);
+    let lines = keys
+        .iter()
+        .zip(&help)
+        .map(|(keys, (_, action))| {
+            Line::from(vec![
+                format!("{keys:key_width$}  ").fg(app.theme.keys),
+                action.help().into(),
+            ])
+        })
+        .collect::<Vec<_>>();
+    let width = lines.iter().map(Line::width).max().unwrap_or(0) as u16;
+
+    // as many columns as needed to fit
+    let rows = area.height.saturating_sub(2).max(1);
+    let columns = (lines.len() as u16).div_ceil(rows).max(1);
+    // 2 spaces between columns, 2 border
+    let popup_width = (columns * (width + 2)).min(area.width);
+    let popup_height = (lines.len() as u16).div_ceil(columns) + 2;
+    let popup = Rect {
+        x: area.x + area.width.saturating_sub(popup_width) / 2,
+        y: area.y + area.height.saturating_sub(popup_height) / 2,
+        width: popup_width,
+        height: popup_height.min(area.height),
+    };
+
+    let block = Block::bordered()
+        .border_type(BorderType::Rounded)
+        .border_style(app.theme.border)
+        .title(format!(" Keys: {mode} "))
+        .title_bottom(" any key to close ");
+    let inner = block.inner(popup);
+    f.render_widget(Clear, popup);
+    f.render_widget(block, popup);
+    let chunks =
+        Layout::horizontal(vec![Constraint::Length(width + 2); columns as usize]).split(inner);
+    for (chunk, column) in chunks.iter().zip(lines.chunks(rows as usize)) {
+        f.render_widget(Paragraph::new(column.to_vec()), *chunk);
+    }
+}
+
+/// ```text
+///  _patterns___________
+/// |/query              |
+/// |> glider 3×3, 5     |
+/// |  acorn 7×3, 7      |
+/// |____________________|
+/// ```
+fn render_browser(f: &mut Frame, browser: &mut Browser, keymap: &Keymap, theme: Theme, area: Rect) {
+    let block = Block::bordered()
+        .border_type(BorderType::Rounded)
+        .title(" Patterns ");
+    let inner = block.inner(area);
+    f.render_widget(Clear, area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).split(inner);
+
+    let search = if browser.searching {
+        Line::from(vec![
+            "/".fg(theme.keys),
+            browser.query.clone().into(),
+            "_".fg(theme.keys),
+        ])
+    } else if browser.query.is_empty() {
+        let hints = hints(
+            keymap,
+            Mode::Browser,
+            &[
+                (&[Action::Search], "search"),
+                (&[Action::Load], "load"),
+                (&[Action::Stamp], "stamp"),
+                (&[Action::Back], "close"),
+                (&[Action::Help], "help"),
+            ],
+        );
+        hints.fg(theme.keys).into()
+    } else {
+        Line::from(vec!["/".fg(theme.keys), browser.query.clone().into()])
+    };
+    f.render_widget(search, chunks[0]);
+
+    let items = browser
+        .matches()
+        .into_iter()
+        .map(|e| {
+            let info = format!(
+                " {}×{}, {} alive",
+                e.area.width, e.area.height, e.population
+            );
+            ListItem::new(Line::from(vec![e.name.clone().into(), info.fg(theme.info)]))
+        })
+        .collect::<Vec<_>>();
+    let list = List::new(items)
+        .highlight_symbol("> ")
+        .highlight_style(ratatui::style::Style::new().reversed());
+    f.render_stateful_widget(list, chunks[1], &mut browser.state);
+}
+
+/// ```text
+///  _bookmarks_______________
+/// |[enter] go back, [d] del.|
+/// |> gen 120 40×20, 84 alive|
+/// |  gen 300 40×20, 96 alive|
+/// |_________________________|
+/// ```
+fn render_bookmarks(
+    f: &mut Frame,
+    bookmarks: &mut Bookmarks,
+    keymap: &Keymap,
+    theme: Theme,
+    area: Rect,
+) {
+    let block = Block::bordered()
+        .border_type(BorderType::Rounded)
+        .title(" Bookmarks ");
+    let inner = block.inner(area);
+    f.render_widget(Clear, area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).split(inner);
+    let hint = if bookmarks.list.is_empty() {
+        hints(
+            keymap,
+            Mode::Bookmarks,
+            &[
+                (&[Action::Bookmark], "bookmark this generation"),
+                (&[Action::Help], "help"),
+            ],
+        )
+    } else {
+        hints(
+            keymap,
+            Mode::Bookmarks,
+            &[
+                (&[Action::Load], "go back"),
+                (&[Action::Delete], "delete"),
+                (&[Action::SaveBookmarks], "write"),
+                (&[Action::Help], "help"),
+            ],
+        )
+    };
+    f.render_widget(hint.fg(theme.keys), chunks[0]);
+
+    let items = bookmarks
+        .list
+        .iter()
+        .map(|mark| {
+            let info = format!(
+                " {}×{}, {} alive",
+                mark.universe.width(),
+                mark.universe.height(),
+                mark.universe.population()
+            );
+            let generation = format!("gen {}", mark.generation);
+            ListItem::new(Line::from(vec![generation.into(), info.fg(theme.info)]))
+        })
+        .collect::<Vec<_>>();
+    let list = List::new(items)
+        .highlight_symbol("> ")
+        .highlight_style(ratatui::style::Style::new().reversed());
+    if let Some(state) = bookmarks.panel.as_mut() {
+        f.render_stateful_widget(list, chunks[1], state);
+    }
+}
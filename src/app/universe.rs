@@ -1,13 +1,61 @@
+use super::heat::HeatMap;
 use super::shapes;
-use crate::{app::Area, app::Cell};
+use crate::{app::Area, app::Cell, app::Error, app::ParseError, app::Rule, app::Theme};
 use ratatui::{style::Color, widgets::canvas::Shape};
 
 /// the `Universe` in which game plays. Represented as a `Vec` of `Cell`s.
-#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Eq, Default)]
 pub struct Universe {
     pub area: Area,
     pub cells: Vec<Cell>,
     pub name: String,
+    /// the rule by which it evolves
+    pub rule: Rule,
+    pub topology: Topology,
+    /// generations each cell has been in its current state, empty until the first `tick`
+    ages: Vec<u16>,
+}
+/// Ages aren't compared: they're the history, not the state
+impl PartialEq for Universe {
+    fn eq(&self, other: &Self) -> bool {
+        self.area == other.area
+            && self.cells == other.cells
+            && self.name == other.name
+            && self.rule == other.rule
+            && self.topology == other.topology
+    }
+}
+
+/// What's beyond the edges of the `Universe`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Topology {
+    /// the opposite edge: edges wrap around
+    #[default]
+    Torus,
+    /// dead cells: nothing gets past the edges
+    Plane,
+}
+impl std::fmt::Display for Topology {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Topology::Torus => write!(f, "torus"),
+            Topology::Plane => write!(f, "plane"),
+        }
+    }
+}
+impl std::str::FromStr for Topology {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "torus" => Ok(Self::Torus),
+            "plane" => Ok(Self::Plane),
+            _ => Err(Error::Invalid {
+                what: "topology",
+                value: s.to_string(),
+            }),
+        }
+    }
 }
 impl<U1: Into<usize>, U2: Into<usize>> std::ops::Index<(U1, U2)> for Universe {
     type Output = Cell;
@@ -34,8 +82,17 @@ impl Universe {
             area,
             cells,
             name: name.to_string(),
+            rule: Rule::default(),
+            topology: Topology::default(),
+            ages: vec![],
         }
     }
+    pub fn with_rule(self, rule: Rule) -> Self {
+        Self { rule, ..self }
+    }
+    pub fn with_topology(self, topology: Topology) -> Self {
+        Self { topology, ..self }
+    }
     pub fn with_name(self, name: impl ToString) -> Self {
         Self {
             name: name.to_string(),
@@ -45,6 +102,13 @@ impl Universe {
     pub fn name(&self) -> &str {
         &self.name
     }
+    /// Number of `Alive` cells
+    pub fn population(&self) -> usize {
+        self.cells
+            .iter()
+            .filter(|cell| **cell == Cell::Alive)
+            .count()
+    }
 
     fn get_idx(&self, coord: (impl Into<usize>, impl Into<usize>)) -> usize {
         let row = coord.0.into();
@@ -70,6 +134,9 @@ impl Universe {
     }
 
     pub fn live_neighbour_count(&self, row: u16, col: u16) -> u8 {
+        if self.topology == Topology::Plane {
+            return self.bounded_neighbour_count(row, col);
+        }
         let mut sum = 0;
 
         for delta_row in [self.area.height - 1, 0, 1] {
@@ -86,21 +153,37 @@ impl Universe {
         }
         sum
     }
+    /// Number of live neighbours, cells outside of the universe being dead
+    fn bounded_neighbour_count(&self, row: u16, col: u16) -> u8 {
+        let mut sum = 0;
+        let rows = row.saturating_sub(1)..=(row + 1).min(self.area.height - 1);
+        for neighbour_row in rows {
+            let cols = col.saturating_sub(1)..=(col + 1).min(self.area.width - 1);
+            for neighbour_col in cols {
+                if (neighbour_row, neighbour_col) != (row, col) {
+                    sum += self[(neighbour_row, neighbour_col)] as u8;
+                }
+            }
+        }
+        sum
+    }
 
-    /// Convert properly formatted Vec of Strings to Universe
-    fn from_vec_str(s: &[String]) -> Result<Self, String> {
-        let (metadata, pattern): (Vec<_>, Vec<_>) = s.iter().partition(|l| l.starts_with('!'));
+    /// Convert properly formatted lines to Universe.
+    /// Each line comes with its position: line number and column it starts at, both 1-based
+    fn from_lines(s: &[(usize, usize, &str)]) -> Result<Self, Error> {
+        let (metadata, pattern): (Vec<_>, Vec<_>) =
+            s.iter().partition(|(_, _, l)| l.starts_with('!'));
 
         let width = pattern
             .iter()
-            .map(|ln| ln.chars().count())
+            .map(|(_, _, ln)| ln.chars().count())
             .max()
             .unwrap_or(0) as u16;
         let height = pattern.len() as u16;
         let area = Area::new(width, height);
         let mut univ = shapes::empty(area);
 
-        if let Some(name) = metadata.first() {
+        if let Some((_, _, name)) = metadata.first() {
             let name = name
                 .replace(".cells", "")
                 .replace('!', "")
@@ -109,9 +192,13 @@ impl Universe {
             univ.name = name.to_string();
         };
 
-        for (i, line) in pattern.iter().enumerate() {
-            for (j, ch) in line.chars().enumerate() {
-                univ[(i, j)] = ch.try_into()?;
+        for (i, (line, col, pattern_line)) in pattern.iter().enumerate() {
+            for (j, ch) in pattern_line.chars().enumerate() {
+                univ[(i, j)] = ch.try_into().map_err(|e: Error| ParseError {
+                    line: *line,
+                    col: col + j,
+                    reason: e.to_string(),
+                })?;
             }
         }
 
@@ -122,22 +209,21 @@ impl Universe {
     ///
     /// # Errors
     ///
-    /// if shape can't fit universe
-    pub fn from_figur(area: Area, figur: Universe) -> Result<Universe, ()> {
-        let count_alive = |univ: &Universe| -> usize {
-            univ.cells
-                .iter()
-                .filter(|cell| *cell == &Cell::Alive)
-                .count()
-        };
-
-        let figur_alive = count_alive(&figur);
-
-        if area < figur.area {
-            return Err(());
+    /// `Error::TooBig` if shape can't fit universe
+    pub fn from_figur(area: Area, figur: Universe) -> Result<Universe, Error> {
+        let figur_alive = figur.population();
+
+        if !area.fits(figur.area) {
+            return Err(Error::TooBig {
+                figure: figur.area,
+                area,
+            });
         }
 
-        let mut univ = shapes::empty(area).with_name(figur.name());
+        let mut univ = shapes::empty(area)
+            .with_name(figur.name())
+            .with_rule(figur.rule)
+            .with_topology(figur.topology);
 
         let (start_row, start_col) = (
             (area.height - figur.height()) / 2,
@@ -152,10 +238,35 @@ impl Universe {
             }
         }
 
-        assert_eq!(figur_alive, count_alive(&univ), "faulty algorithm");
+        assert_eq!(figur_alive, univ.population(), "faulty algorithm");
         Ok(univ)
     }
 
+    /// Generations the cell at `at`: (row, col) has been in its current state, if known
+    pub fn age(&self, at: (u16, u16)) -> Option<u16> {
+        let idx = self.get_idx(at);
+        self.ages.get(idx).copied()
+    }
+    /// Indices of the cells changed by the last `tick`
+    pub fn changed(&self) -> impl Iterator<Item = usize> + '_ {
+        self.ages
+            .iter()
+            .enumerate()
+            .filter(|(_, &age)| age == 0)
+            .map(|(i, _)| i)
+    }
+    /// Cells different from the ones in `before` are 0 generations old, eg. after an edit
+    pub fn reset_ages(&mut self, before: &Universe) {
+        if self.ages.len() != self.cells.len() || before.cells.len() != self.cells.len() {
+            return;
+        }
+        for (i, age) in self.ages.iter_mut().enumerate() {
+            if self.cells[i] != before.cells[i] {
+                *age = 0;
+            }
+        }
+    }
+
     /// update life: `Universe`
     pub fn tick(&mut self) {
         let mut next = self.clone();
@@ -166,30 +277,137 @@ impl Universe {
                 let cell = self[idx];
                 let live_neighbours = self.live_neighbour_count(row, col);
 
-                let next_cell = match (cell, live_neighbours) {
-                    // Rule 1: Any live cell with fewer than two live neighbours
-                    // dies, as if caused by underpopulation.
-                    (Cell::Alive, n) if n < 2 => Cell::Dead,
-                    // Rule 2: Any live cell with two or three live neighbours
-                    // lives on to the next generation.
-                    (Cell::Alive, 2 | 3) => Cell::Alive,
-                    // Rule 3: Any live cell with more than three live
-                    // neighbours dies, as if by overpopulation.
-                    (Cell::Alive, n) if n > 3 => Cell::Dead,
-                    // Rule 4: Any dead cell with exactly three live neighbours
-                    // becomes a live cell, as if by reproduction.
-                    (Cell::Dead, 3) => Cell::Alive,
-                    // All other cells remain in the same state.
-                    (otherwise, _) => otherwise,
-                };
-
-                next[idx] = next_cell;
+                next[idx] = self.rule.apply(cell, live_neighbours);
             }
         }
+        let known = self.ages.len() == self.cells.len();
+        next.ages = (0..self.cells.len())
+            .map(|i| match (next.cells[i] == self.cells[i], known) {
+                (false, _) => 0,
+                (true, true) => self.ages[i].saturating_add(1),
+                (true, false) => 1,
+            })
+            .collect();
 
         *self = next;
     }
 
+    /// A `Universe` of size `area`, the cell at (row, col) being the one at `src(row, col)` in `self`
+    fn remap(&self, area: Area, src: impl Fn(usize, usize) -> (usize, usize)) -> Universe {
+        let width = area.width as usize;
+        let cells = (0..area.len())
+            .map(|i| self[src(i / width, i % width)])
+            .collect();
+        Universe {
+            area,
+            cells,
+            ages: vec![],
+            ..self.clone()
+        }
+    }
+    /// Rotated clockwise by `quarter_turns` × 90°
+    pub fn rotate(&self, quarter_turns: u8) -> Universe {
+        let (w, h) = (self.width() as usize, self.height() as usize);
+        let swapped = Area::new(self.height(), self.width());
+        match quarter_turns % 4 {
+            1 => self.remap(swapped, |row, col| (h - 1 - col, row)),
+            2 => self.remap(self.area, |row, col| (h - 1 - row, w - 1 - col)),
+            3 => self.remap(swapped, |row, col| (col, w - 1 - row)),
+            _ => self.clone(),
+        }
+    }
+    /// Mirrored: left becomes right
+    pub fn flip_horizontal(&self) -> Universe {
+        let w = self.width() as usize;
+        self.remap(self.area, |row, col| (row, w - 1 - col))
+    }
+    /// Mirrored: top becomes bottom
+    pub fn flip_vertical(&self) -> Universe {
+        let h = self.height() as usize;
+        self.remap(self.area, |row, col| (h - 1 - row, col))
+    }
+    /// Mirrored along the main diagonal: rows become columns
+    pub fn transpose(&self) -> Universe {
+        self.remap(Area::new(self.height(), self.width()), |row, col| {
+            (col, row)
+        })
+    }
+    /// The smallest part holding all the live cells: where it starts, (row, col), and its size,
+    /// `None` if there are none
+    pub fn bounds(&self) -> Option<((u16, u16), Area)> {
+        let alive = (0..self.cells.len())
+            .filter(|&i| self.cells[i] == Cell::Alive)
+            .map(|i| (i / self.width() as usize, i % self.width() as usize));
+        let (top, bottom, left, right) = alive.fold(None, |bounds, (row, col)| {
+            let (t, b, l, r) = bounds.unwrap_or((row, row, col, col));
+            Some((t.min(row), b.max(row), l.min(col), r.max(col)))
+        })?;
+        let area = Area::new((right - left + 1) as u16, (bottom - top + 1) as u16);
+        Some(((top as u16, left as u16), area))
+    }
+    /// Cropped to the smallest area holding all the live cells
+    pub fn trim(&self) -> Universe {
+        let Some(((top, left), area)) = self.bounds() else {
+            return self.remap(Area::default(), |row, col| (row, col));
+        };
+        let (top, left) = (top as usize, left as usize);
+        self.remap(area, |row, col| (top + row, left + col))
+    }
+    /// Surrounded by `n` dead cells on each side
+    ///
+    /// # Errors
+    ///
+    /// `Error::Invalid` if it would be too big
+    pub fn pad(&self, n: u16) -> Result<Universe, Error> {
+        let size = |len: u16| n.checked_mul(2).and_then(|n| len.checked_add(n));
+        let (Some(width), Some(height)) = (size(self.width()), size(self.height())) else {
+            return Err(Error::Invalid {
+                what: "padding",
+                value: format!("{n} around {}×{}", self.width(), self.height()),
+            });
+        };
+        Universe::from_figur(Area::new(width, height), self.clone())
+    }
+    /// The part of size `area` starting at `at`: (row, col), cut off at the edges
+    pub fn crop(&self, at: (u16, u16), area: Area) -> Universe {
+        let (row, col) = at;
+        let area = Area::new(
+            area.width.min(self.width().saturating_sub(col)),
+            area.height.min(self.height().saturating_sub(row)),
+        );
+        let (row, col) = (row as usize, col as usize);
+        self.remap(area, |r, c| (row + r, col + c))
+    }
+    /// Set the part of size `area` starting at `at`: (row, col) to `cell`
+    pub fn fill(&mut self, at: (u16, u16), area: Area, cell: Cell) {
+        let (row, col) = at;
+        for r in row..(row + area.height).min(self.height()) {
+            for c in col..(col + area.width).min(self.width()) {
+                self[(r, c)] = cell;
+            }
+        }
+    }
+    /// Bring the live cells of `figur` to life starting at `at`: (row, col), cut off at the edges
+    pub fn stamp(&mut self, at: (u16, u16), figur: &Universe) {
+        let (row, col) = at;
+        for r in 0..figur.height().min(self.height().saturating_sub(row)) {
+            for c in 0..figur.width().min(self.width().saturating_sub(col)) {
+                if figur[(r, c)] == Cell::Alive {
+                    self[(row + r, col + c)] = Cell::Alive;
+                }
+            }
+        }
+    }
+    /// Overwrite the part starting at `at`: (row, col) with `figur`, cut off at the edges
+    pub fn paste(&mut self, at: (u16, u16), figur: &Universe) {
+        let (row, col) = at;
+        for r in 0..figur.height().min(self.height().saturating_sub(row)) {
+            for c in 0..figur.width().min(self.width().saturating_sub(col)) {
+                self[(row + r, col + c)] = figur[(r, c)];
+            }
+        }
+    }
+
     pub fn width(&self) -> u16 {
         self.area.width
     }
@@ -199,16 +417,92 @@ impl Universe {
     }
 }
 impl std::str::FromStr for Universe {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let v = s
-            .trim()
+        let mut v = s
             .lines()
-            .map(str::trim)
-            .map(std::convert::Into::into)
-            .collect::<Vec<String>>();
-        Self::from_vec_str(&v)
+            .enumerate()
+            .map(|(i, ln)| {
+                let indent = ln.chars().take_while(|c| c.is_whitespace()).count();
+                (i + 1, indent + 1, ln.trim())
+            })
+            .skip_while(|(_, _, ln)| ln.is_empty())
+            .collect::<Vec<_>>();
+        while v.last().is_some_and(|(_, _, ln)| ln.is_empty()) {
+            v.pop();
+        }
+        Self::from_lines(&v)
+    }
+}
+
+/// The part of a `Universe` of size `area`, starting at `offset`: (row, col)
+#[derive(Debug, Clone, Copy)]
+pub struct View<'a> {
+    pub universe: &'a Universe,
+    pub offset: (u16, u16),
+    pub area: Area,
+    pub theme: Theme,
+    /// colouring by age
+    pub aging: bool,
+    /// dead cells coloured by how often they changed
+    pub heat: Option<&'a HeatMap>,
+}
+impl Universe {
+    /// The part of `self` of size `area`, starting at `offset`: (row, col)
+    pub fn view(&self, offset: (u16, u16), area: Area) -> View<'_> {
+        View {
+            universe: self,
+            offset,
+            area,
+            theme: Theme::default(),
+            aging: false,
+            heat: None,
+        }
+    }
+}
+impl<'a> View<'a> {
+    pub fn with_colors(self, theme: Theme, aging: bool) -> Self {
+        Self {
+            theme,
+            aging,
+            ..self
+        }
+    }
+    pub fn with_heat(self, heat: Option<&'a HeatMap>) -> Self {
+        Self { heat, ..self }
+    }
+    /// Colour of the cell at `at`: (row, col) of the universe, `None` if it's not drawn
+    pub fn color(&self, at: (u16, u16)) -> Option<Color> {
+        let (row, col) = at;
+        if row >= self.universe.height() || col >= self.universe.width() {
+            return None;
+        }
+        let age = self.aging.then(|| self.universe.age(at)).flatten();
+        let color = self.theme.color(self.universe[at], age);
+        let Some(heat) = self.heat else {
+            return color;
+        };
+        let heat = heat.heat(self.universe.get_idx(at));
+        match color {
+            // live cells keep a hint of their own colour
+            Some(color) => Some(HeatMap::tint(color, heat)),
+            None => HeatMap::color(heat),
+        }
+    }
+}
+impl Shape for View<'_> {
+    fn draw(&self, painter: &mut ratatui::widgets::canvas::Painter) {
+        let (row, col) = self.offset;
+        let rows = row..(row + self.area.height).min(self.universe.height());
+        let cols = col..(col + self.area.width).min(self.universe.width());
+        for y in rows {
+            for x in cols.clone() {
+                if let Some(color) = self.color((y, x)) {
+                    painter.paint((x - col).into(), (y - row).into(), color);
+                }
+            }
+        }
     }
 }
 
@@ -0,0 +1,544 @@
+use crate::{
+    app::{
+        format::Format,
+        load,
+        record::{self, Recorder},
+        shapes, Area, Topology, Universe,
+    },
+    cli,
+};
+use std::{
+    collections::HashMap,
+    fs::File,
+    hash::{Hash, Hasher},
+    io::{BufWriter, Read, Write},
+    path::Path,
+    time::Instant,
+};
+
+#[cfg(test)]
+mod tests;
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// Default size of the random soup benchmarked
+const BENCH_SIZE: Area = Area {
+    width: 256,
+    height: 256,
+};
+
+/// Load the single pattern at `path`, `-` being stdin
+///
+/// # Errors
+///
+/// the pattern can't be loaded, or `path` is a directory of many
+pub fn load_one(path: &Path) -> Result<Universe> {
+    if path.as_os_str() == "-" {
+        let mut s = String::new();
+        std::io::stdin().read_to_string(&mut s)?;
+        return Ok(load::parse(path, &s)?);
+    }
+    let mut universes = load::load(path);
+    if universes.len() != 1 {
+        return Err(format!("{} isn't a single pattern", path.display()).into());
+    }
+    Ok(universes.remove(0)?)
+}
+
+/// Find a pattern by path, or by name among the built-in and library patterns
+///
+/// # Errors
+///
+/// there's no such pattern, or it can't be loaded
+pub fn find(name: &str) -> Result<Universe> {
+    let path = Path::new(name);
+    if name == "-" || path.exists() {
+        return load_one(path);
+    }
+    let library = load::default_library()
+        .filter(|path| path.is_dir())
+        .map(|path| load::load(&path))
+        .unwrap_or_default();
+    shapes::all()
+        .into_iter()
+        .chain(library.into_iter().flatten())
+        .find(|univ| univ.name().eq_ignore_ascii_case(name))
+        .ok_or_else(|| format!("no pattern file or pattern named {name}").into())
+}
+
+/// Hash of the cells and their arrangement
+fn hash(univ: &Universe) -> u64 {
+    let mut hasher = std::hash::DefaultHasher::new();
+    (univ.width(), univ.height()).hash(&mut hasher);
+    univ.cells.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Dead cells kept around the live ones when a board grows
+const ROOM: u16 = 8;
+
+/// A universe run without a TUI: of fixed size if `--size` is given or the topology is a torus,
+/// otherwise on an unbounded plane, the board growing whenever live cells reach its edges
+#[derive(Debug, Clone)]
+pub struct Board {
+    pub univ: Universe,
+    /// whether the board grows with the live cells
+    unbounded: bool,
+    /// position of the top left cell: (row, col), relative to where the pattern started
+    origin: (i64, i64),
+}
+impl Board {
+    /// `univ` on an unbounded plane
+    pub fn unbounded(univ: Universe) -> Self {
+        let mut board = Self {
+            univ: univ.with_topology(Topology::Plane),
+            unbounded: true,
+            origin: (0, 0),
+        };
+        board.grow();
+        board
+    }
+    /// `univ` as it is
+    pub fn fixed(univ: Universe) -> Self {
+        Self {
+            univ,
+            unbounded: false,
+            origin: (0, 0),
+        }
+    }
+    pub fn is_unbounded(&self) -> bool {
+        self.unbounded
+    }
+    pub fn tick(&mut self) {
+        self.univ.tick();
+        self.grow();
+    }
+    /// Make room around the live cells if they've reached the edges,
+    /// so that nothing born next generation is lost
+    fn grow(&mut self) {
+        if !self.unbounded {
+            return;
+        }
+        let Some(((top, left), area)) = self.univ.bounds() else {
+            return;
+        };
+        let (bottom, right) = (top + area.height, left + area.width);
+        if top > 0 && left > 0 && bottom < self.univ.height() && right < self.univ.width() {
+            return;
+        }
+        // as big as a board gets: cells reaching its edges are lost from now on
+        let Ok(univ) = self.univ.trim().pad(ROOM) else {
+            return;
+        };
+        self.univ = univ;
+        self.origin.0 += i64::from(top) - i64::from(ROOM);
+        self.origin.1 += i64::from(left) - i64::from(ROOM);
+    }
+    /// The live cells: where they start, (row, col) relative to where the pattern started, and their part of the board
+    pub fn live(&self) -> ((i64, i64), Universe) {
+        let (top, left) = self.univ.bounds().map_or((0, 0), |(at, _)| at);
+        let at = (
+            self.origin.0 + i64::from(top),
+            self.origin.1 + i64::from(left),
+        );
+        (at, self.univ.trim())
+    }
+    /// The board, or only its live cells if it's unbounded
+    pub fn result(&self) -> Universe {
+        if self.unbounded {
+            self.univ.trim()
+        } else {
+            self.univ.clone()
+        }
+    }
+}
+
+/// Apply the settings of `sim` to `univ`
+///
+/// # Errors
+///
+/// `univ` doesn't fit into `sim.size`
+pub fn prepare(univ: Universe, sim: &cli::Sim) -> Result<Board> {
+    let univ = match sim.rule {
+        Some(rule) => univ.with_rule(rule),
+        None => univ,
+    };
+    Ok(match (sim.size, sim.topology) {
+        (Some(size), topology) => Board::fixed(
+            Universe::from_figur(size, univ)?.with_topology(topology.unwrap_or_default()),
+        ),
+        (None, Some(Topology::Torus)) => Board::fixed(univ.with_topology(Topology::Torus)),
+        (None, _) => Board::unbounded(univ),
+    })
+}
+
+/// Run a pattern for `generations`, then write it to `out`
+///
+/// # Errors
+///
+/// loading or writing failed
+pub fn run(args: &cli::Run, out: &mut impl Write) -> Result<()> {
+    let mut board = prepare(load_one(&args.pattern)?, &args.sim)?;
+    let mut stderr = std::io::stderr().lock();
+    if args.population {
+        writeln!(stderr, "0 {}", board.univ.population())?;
+    }
+    for gen in 1..=args.generations {
+        board.tick();
+        if args.population {
+            writeln!(stderr, "{gen} {}", board.univ.population())?;
+        }
+    }
+    write!(out, "{}", args.format.write(&board.result()))?;
+    Ok(())
+}
+
+/// Record generations of a pattern as an animated GIF
+///
+/// # Errors
+///
+/// loading or writing failed
+pub fn gif(args: &cli::Gif, out: &mut impl Write) -> Result<()> {
+    let mut board = prepare(find(&args.pattern)?, &args.sim)?;
+    for _ in 0..args.from {
+        board.tick();
+    }
+    // the live cells of each generation recorded, where they are
+    let mut frames = vec![];
+    for i in 0..args.generations {
+        if i > 0 {
+            board.tick();
+        }
+        frames.push(if board.is_unbounded() {
+            board.live()
+        } else {
+            ((0, 0), board.univ.clone())
+        });
+    }
+    // an unbounded board is recorded as far as the live cells got, with a cell of room
+    let margin = i64::from(board.is_unbounded());
+    let top = frames.iter().map(|((row, _), _)| *row).min().unwrap_or(0) - margin;
+    let left = frames.iter().map(|((_, col), _)| *col).min().unwrap_or(0) - margin;
+    let bottom = frames
+        .iter()
+        .map(|((row, _), univ)| row + i64::from(univ.height()))
+        .max()
+        .unwrap_or(0)
+        + margin;
+    let right = frames
+        .iter()
+        .map(|((_, col), univ)| col + i64::from(univ.width()))
+        .max()
+        .unwrap_or(0)
+        + margin;
+    let area = Area::new(u16::try_from(right - left)?, u16::try_from(bottom - top)?);
+
+    let style = record::Style {
+        cell: args.cell,
+        alive: args.alive,
+        dead: args.dead,
+    };
+    let out: Box<dyn Write + '_> = match &args.output {
+        Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+        None => Box::new(out),
+    };
+    let mut recorder = Recorder::new(out, area, style)?;
+    for ((row, col), univ) in frames {
+        let mut frame = shapes::empty(area);
+        frame.paste(((row - top) as u16, (col - left) as u16), &univ);
+        recorder.frame(&frame, args.delay)?;
+    }
+    recorder.finish()?.flush()?;
+    Ok(())
+}
+
+/// Convert a pattern to another format
+///
+/// # Errors
+///
+/// loading or writing failed
+pub fn convert(args: &cli::Convert, out: &mut impl Write) -> Result<()> {
+    let mut univ = match args.from {
+        Some(format) => {
+            let mut s = String::new();
+            if args.input.as_os_str() == "-" {
+                std::io::stdin().read_to_string(&mut s)?;
+            } else {
+                s = std::fs::read_to_string(&args.input)
+                    .map_err(|e| load::LoadError::new(&args.input, e))?;
+            }
+            load::parse_as(&args.input, &s, format)?
+        }
+        None => load_one(&args.input)?,
+    };
+
+    if args.trim {
+        univ = univ.trim();
+    }
+    if let Some(quarter_turns) = args.rotate {
+        univ = univ.rotate(quarter_turns);
+    }
+    for flip in &args.flip {
+        univ = match flip {
+            cli::Flip::Horizontal => univ.flip_horizontal(),
+            cli::Flip::Vertical => univ.flip_vertical(),
+        };
+    }
+    if let Some(n) = args.pad {
+        univ = univ.pad(n)?;
+    }
+
+    let format = args
+        .to
+        .or_else(|| args.output.as_deref().and_then(Format::from_path))
+        .unwrap_or_default();
+    let s = format.write(&univ);
+    match &args.output {
+        Some(path) => std::fs::write(path, s)?,
+        None => write!(out, "{s}")?,
+    }
+    Ok(())
+}
+
+/// Run a pattern until a generation repeats, then report what it became
+///
+/// # Errors
+///
+/// loading or writing failed
+pub fn analyze(args: &cli::Analyze, out: &mut impl Write) -> Result<()> {
+    let mut board = prepare(load_one(&args.pattern)?, &args.sim)?;
+    let univ = &board.univ;
+    writeln!(out, "name: {}", univ.name())?;
+    let size = if board.is_unbounded() {
+        "unbounded".to_string()
+    } else {
+        format!("{}×{}", univ.width(), univ.height())
+    };
+    writeln!(
+        out,
+        "size: {size}, rule: {}, topology: {}",
+        univ.rule, univ.topology
+    )?;
+
+    // generation a state was first seen at, where its live cells were:
+    // on an unbounded board the same cells elsewhere are the same state, moved
+    let mut seen = HashMap::new();
+    let (mut min, mut max) = (univ.population(), univ.population());
+    let mut result = None;
+    for gen in 0..=args.max_generations {
+        let population = board.univ.population();
+        (min, max) = (min.min(population), max.max(population));
+        if population == 0 {
+            result = Some(format!("dies out at generation {gen}"));
+            break;
+        }
+        let (at, live) = board.live();
+        let state = if board.is_unbounded() {
+            hash(&live)
+        } else {
+            hash(&board.univ)
+        };
+        if let Some((first, was_at)) = seen.insert(state, (gen, at)) {
+            let period = gen - first;
+            let (rows, cols) = (at.0 - was_at.0, at.1 - was_at.1);
+            result = Some(if (rows, cols) != (0, 0) {
+                format!("moves by {rows},{cols} every {period} generations from generation {first}")
+            } else if period == 1 {
+                format!("becomes a still life at generation {first}")
+            } else {
+                format!("repeats with period {period} from generation {first}")
+            });
+            break;
+        }
+        board.tick();
+    }
+    let result = result.unwrap_or(format!(
+        "doesn't repeat within {} generations",
+        args.max_generations
+    ));
+    writeln!(out, "result: {result}")?;
+    writeln!(
+        out,
+        "population: min {min}, max {max}, final {}",
+        board.univ.population()
+    )?;
+    Ok(())
+}
+
+/// Measure how fast generations of a pattern are computed
+///
+/// # Errors
+///
+/// loading or writing failed
+pub fn bench(args: &cli::Bench, out: &mut impl Write) -> Result<()> {
+    if let Some(seed) = args.seed {
+        fastrand::seed(seed);
+    }
+    let mut board = match &args.pattern {
+        Some(path) => prepare(load_one(path)?, &args.sim)?,
+        None => {
+            let size = args.sim.size.unwrap_or(BENCH_SIZE);
+            let sim = cli::Sim {
+                size: Some(size),
+                ..args.sim.clone()
+            };
+            prepare(shapes::rand(size), &sim)?
+        }
+    };
+
+    let start = Instant::now();
+    for _ in 0..args.generations {
+        board.tick();
+    }
+    let elapsed = start.elapsed();
+    let univ = &board.univ;
+
+    let gens_per_sec = args.generations as f64 / elapsed.as_secs_f64();
+    writeln!(
+        out,
+        "{} generations of {} ({}×{}) in {elapsed:.2?}: {gens_per_sec:.1} gen/s, {:.0} cells/s",
+        args.generations,
+        univ.name(),
+        univ.width(),
+        univ.height(),
+        gens_per_sec * univ.area.len() as f64
+    )?;
+    Ok(())
+}
+
+/// `patterns` placed at `positions`: (row, col), surrounded by `margin` dead cells
+///
+/// # Errors
+///
+/// the patterns are too far apart to fit into a universe
+pub fn compose(patterns: &[Universe], positions: &[(i32, i32)], margin: u16) -> Result<Universe> {
+    let placed = || {
+        patterns
+            .iter()
+            .zip(positions)
+            .map(|(p, &(row, col))| (p, (i64::from(row), i64::from(col))))
+    };
+    let top = placed().map(|(_, (row, _))| row).min().unwrap_or(0);
+    let left = placed().map(|(_, (_, col))| col).min().unwrap_or(0);
+    let bottom = placed()
+        .map(|(p, (row, _))| row + i64::from(p.height()))
+        .max()
+        .unwrap_or(0);
+    let right = placed()
+        .map(|(p, (_, col))| col + i64::from(p.width()))
+        .max()
+        .unwrap_or(0);
+    let margin = i64::from(margin);
+    let (width, height) = (right - left + 2 * margin, bottom - top + 2 * margin);
+    let (Ok(w), Ok(h)) = (u16::try_from(width), u16::try_from(height)) else {
+        return Err(format!(
+            "patterns too far apart: {width}×{height} with the margin, at most {}×{}",
+            u16::MAX,
+            u16::MAX
+        )
+        .into());
+    };
+    let area = Area::new(w, h);
+    let name = patterns
+        .iter()
+        .map(Universe::name)
+        .collect::<Vec<_>>()
+        .join(" + ");
+    let rule = patterns.first().map(|p| p.rule).unwrap_or_default();
+    let mut univ = shapes::empty(area).with_name(name).with_rule(rule);
+    for (p, (row, col)) in placed() {
+        // inside the area, so within u16
+        let at = ((row - top + margin) as u16, (col - left + margin) as u16);
+        univ.stamp(at, p);
+    }
+    Ok(univ)
+}
+
+/// Collide patterns at every offset of the sweep, then report the distinct outcomes
+///
+/// # Errors
+///
+/// loading or writing failed
+pub fn lab(args: &cli::Lab, out: &mut impl Write) -> Result<()> {
+    let mut patterns = vec![];
+    for (i, name) in args.patterns.iter().enumerate() {
+        let mut univ = find(name)?;
+        if let Some(rule) = args.sim.rule {
+            univ.rule = rule;
+        }
+        let orientation = args.orient.get(i).copied().unwrap_or_default();
+        let phase = args.phase.get(i).copied().unwrap_or(0);
+        let mut board = Board::unbounded(orientation.apply(&univ));
+        for _ in 0..phase {
+            board.tick();
+        }
+        patterns.push(board.result());
+    }
+    let mut positions = (0..patterns.len())
+        .map(|i| args.at.get(i).copied().unwrap_or_default())
+        .collect::<Vec<_>>();
+    let last = positions.len() - 1;
+    let (row, col) = positions[last];
+    // debris flies off instead of wrapping around into the reaction
+    let sim = cli::Sim {
+        topology: Some(args.sim.topology.unwrap_or(Topology::Plane)),
+        ..args.sim.clone()
+    };
+
+    // outcomes in the order they were first seen: final universe, offsets
+    let mut outcomes: Vec<(Universe, Vec<_>)> = vec![];
+    let mut index = HashMap::new();
+    for d_row in args.rows.clone() {
+        for d_col in args.cols.clone() {
+            positions[last] = (row.saturating_add(d_row), col.saturating_add(d_col));
+            let mut board = prepare(compose(&patterns, &positions, args.margin)?, &sim)?;
+            for _ in 0..args.generations {
+                board.tick();
+            }
+            let univ = board.univ.trim();
+            let key = if args.census {
+                univ.population() as u64
+            } else {
+                hash(&univ)
+            };
+            let i = *index.entry(key).or_insert_with(|| {
+                outcomes.push((univ, vec![]));
+                outcomes.len() - 1
+            });
+            outcomes[i].1.push((d_row, d_col));
+        }
+    }
+
+    let swept = outcomes
+        .iter()
+        .map(|(_, offsets)| offsets.len())
+        .sum::<usize>();
+    writeln!(
+        out,
+        "{}: {swept} offsets, {} distinct outcomes after {} generations",
+        args.patterns.join(" + "),
+        outcomes.len(),
+        args.generations
+    )?;
+    for (i, (univ, offsets)) in outcomes.iter().enumerate() {
+        let result = if univ.population() == 0 {
+            "dies out".to_string()
+        } else if args.census {
+            format!("population {}", univ.population())
+        } else {
+            format!(
+                "population {} in {}×{}",
+                univ.population(),
+                univ.width(),
+                univ.height()
+            )
+        };
+        let offsets = offsets
+            .iter()
+            .map(|(row, col)| format!("{row},{col}"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        writeln!(out, "outcome {}: {result}, at offsets {offsets}", i + 1)?;
+    }
+    Ok(())
+}
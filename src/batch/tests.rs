@@ -0,0 +1,290 @@
+use super::*;
+use crate::{
+    app::{shapes, tests::TempDir, Orientation},
+    cli::{Cli, Command},
+};
+use clap::Parser;
+use std::str::FromStr;
+
+const BLOCK: &str = "\
+!Name: Block
+OO
+OO";
+
+fn command(args: &[&str]) -> Command {
+    Cli::try_parse_from(std::iter::once("cgol-tui").chain(args.iter().copied()))
+        .unwrap()
+        .command
+        .unwrap()
+}
+
+/// Error of the headless `lab` with `args`
+fn lab_error(args: &[&str]) -> String {
+    let mut all = vec!["lab"];
+    all.extend(args);
+    let Command::Lab(args) = command(&all) else {
+        unreachable!()
+    };
+    lab(&args, &mut vec![]).unwrap_err().to_string()
+}
+
+/// Output of the headless `args`
+fn output(args: &[&str]) -> String {
+    let mut out = vec![];
+    match command(args) {
+        Command::Run(args) => run(&args, &mut out),
+        Command::Analyze(args) => analyze(&args, &mut out),
+        Command::Bench(args) => bench(&args, &mut out),
+        Command::Lab(args) => lab(&args, &mut out),
+        command => panic!("not headless: {command:?}"),
+    }
+    .unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+fn universe(s: &str) -> Universe {
+    Universe::from_str(s).unwrap()
+}
+
+#[test]
+fn run_unbounded() {
+    let dir = TempDir::new("run");
+    let block = dir.file("block.cells", BLOCK).display().to_string();
+    let glider = dir
+        .file("glider.cells", shapes::GLIDER)
+        .display()
+        .to_string();
+
+    let ran = output(&["run", "-n", "1", &block]);
+    assert_eq!(universe(&ran).cells, universe(BLOCK).cells, "{ran}");
+    // a glider is the same 4 generations later, moved by a cell down and right
+    let ran = output(&["run", "-n", "4", &glider]);
+    assert_eq!(
+        universe(&ran).cells,
+        universe(shapes::GLIDER).cells,
+        "{ran}"
+    );
+    let ran = output(&["run", "-n", "2", "--format", "rle", &glider]);
+    assert!(ran.contains("x = 3, y = 3"), "{ran}");
+
+    let mut board = prepare(universe(shapes::GLIDER), &sim(&[])).unwrap();
+    assert!(board.is_unbounded());
+    for _ in 0..400 {
+        board.tick();
+    }
+    let (at, live) = board.live();
+    assert_eq!(at, (100, 100));
+    assert_eq!(live.cells, universe(shapes::GLIDER).cells);
+    assert!(board.univ.width() < 3 * ROOM);
+
+    // a torus or a size is kept as it is
+    let mut torus = prepare(universe(BLOCK), &sim(&["--topology", "torus"])).unwrap();
+    torus.tick();
+    assert_eq!(torus.result().population(), 0);
+    let ran = output(&["run", "-n", "8", "--size", "5x5", &glider]);
+    let ran = universe(&ran);
+    assert_eq!(ran.area, Area::new(5, 5));
+    assert_eq!(ran.population(), 5);
+}
+
+/// Settings of the simulation from `args`
+fn sim(args: &[&str]) -> cli::Sim {
+    let mut all = vec!["run", "-n", "0", "-"];
+    all.extend(args);
+    match command(&all) {
+        Command::Run(args) => args.sim,
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn analyze_results() {
+    let dir = TempDir::new("analyze");
+    let result = |pattern: &str, args: &[&str]| {
+        let path = dir.file("pattern.cells", pattern).display().to_string();
+        let mut all = vec!["analyze", path.as_str()];
+        all.extend(args);
+        let out = output(&all);
+        out.lines()
+            .find_map(|line| line.strip_prefix("result: "))
+            .unwrap_or_else(|| panic!("{out}"))
+            .to_string()
+    };
+    assert_eq!(result(BLOCK, &[]), "becomes a still life at generation 0");
+    assert_eq!(
+        result("OOO", &[]),
+        "repeats with period 2 from generation 0"
+    );
+    assert_eq!(
+        result(shapes::GLIDER, &[]),
+        "moves by 1,1 every 4 generations from generation 0"
+    );
+    assert_eq!(result("O", &[]), "dies out at generation 1");
+    // on a 10×10 torus the glider comes back to where it started
+    assert_eq!(
+        result(shapes::GLIDER, &["--size", "10x10"]),
+        "repeats with period 40 from generation 0"
+    );
+    assert_eq!(
+        result(shapes::ACORN, &["-n", "100"]),
+        "doesn't repeat within 100 generations"
+    );
+
+    let path = dir
+        .file("glider.cells", shapes::GLIDER)
+        .display()
+        .to_string();
+    let out = output(&["analyze", &path, "--rule", "B36/S23"]);
+    assert!(out.starts_with("name: Glider\n"), "{out}");
+    assert!(
+        out.contains("size: unbounded, rule: B36/S23, topology: plane"),
+        "{out}"
+    );
+    assert!(out.contains("population: min 5, max 5, final 5"), "{out}");
+    let out = output(&["analyze", &path, "--size", "10x10"]);
+    assert!(
+        out.contains("size: 10×10, rule: B3/S23, topology: torus"),
+        "{out}"
+    );
+}
+
+#[test]
+fn bench_runs() {
+    let out = output(&["bench", "-n", "10", "--seed", "1", "--size", "20x10"]);
+    assert!(out.starts_with("10 generations of "), "{out}");
+    assert!(out.contains("(20×10)"), "{out}");
+    assert!(out.contains("gen/s"), "{out}");
+
+    let dir = TempDir::new("bench");
+    let path = dir.file("block.cells", BLOCK).display().to_string();
+    let out = output(&["bench", "-n", "5", &path]);
+    assert!(out.starts_with("5 generations of Block ("), "{out}");
+}
+
+#[test]
+fn compose_patterns() {
+    let block = universe(BLOCK);
+    let glider = universe(shapes::GLIDER);
+    let univ = compose(&[block.clone(), glider.clone()], &[(0, 0), (-2, 5)], 1).unwrap();
+    assert_eq!(univ.name(), "Block + Glider");
+    // glider from row -2 to 1, block up to row 2, from column 0 to 8
+    assert_eq!(univ.area, Area::new(8 + 2, 4 + 2));
+    assert_eq!(univ.population(), 9);
+    assert_eq!(univ.crop((3, 1), Area::new(2, 2)).cells, block.cells);
+    assert_eq!(univ.crop((1, 6), Area::new(3, 3)).cells, glider.cells);
+    // overlapping ones are merged
+    let univ = compose(&[block.clone(), block.clone()], &[(0, 0), (1, 1)], 0).unwrap();
+    assert_eq!(univ.area, Area::new(3, 3));
+    assert_eq!(univ.population(), 7);
+    // too big for a universe
+    let far = compose(&[block.clone(), block.clone()], &[(0, 0), (0, 70_000)], 0);
+    assert!(far.unwrap_err().to_string().contains("too far apart"));
+    assert!(compose(&[block.clone(), block], &[(0, 0), (0, 0)], 40_000).is_err());
+}
+
+#[test]
+fn lab_sweep() {
+    let dir = TempDir::new("lab");
+    let block = dir.file("block.cells", BLOCK).display().to_string();
+    let lines = |args: &[&str]| {
+        let mut all = vec!["lab"];
+        all.extend(args);
+        output(&all).lines().map(String::from).collect::<Vec<_>>()
+    };
+
+    // blocks too far apart to react: every offset has a different outcome, of the same population
+    let apart = [
+        &block, &block, "--at", "0,0", "--at", "0,10", "--cols", "0..3", "-n", "10",
+    ];
+    let out = lines(&apart);
+    assert!(out[0].ends_with(": 4 offsets, 4 distinct outcomes after 10 generations"));
+    assert_eq!(out[1], "outcome 1: population 8 in 12×2, at offsets 0,0");
+    assert_eq!(out[4], "outcome 4: population 8 in 15×2, at offsets 0,3");
+    let out = lines(&[&apart[..], &["--census"]].concat());
+    assert!(out[0].ends_with(": 4 offsets, 1 distinct outcomes after 10 generations"));
+    assert_eq!(
+        out[1],
+        "outcome 1: population 8, at offsets 0,0 0,1 0,2 0,3"
+    );
+
+    // the last pattern is moved by the offsets
+    let out = lines(&[
+        "glider", &block, "--at", "0,0", "--at", "4,4", "--rows", "-1..1", "--cols", "-1..=1",
+        "-n", "100",
+    ]);
+    assert_eq!(
+        out,
+        [
+            format!("glider + {block}: 9 offsets, 2 distinct outcomes after 100 generations"),
+            "outcome 1: population 6 in 3×4, at offsets -1,-1".into(),
+            "outcome 2: dies out, at offsets -1,0 -1,1 0,-1 0,0 0,1 1,-1 1,0 1,1".into(),
+        ]
+    );
+
+    // parallel gliders fly on, they don't come back around
+    let out = lines(&[
+        "glider", "glider", "--at", "0,0", "--at", "0,20", "-n", "200",
+    ]);
+    assert_eq!(out[1], "outcome 1: population 10 in 23×3, at offsets 0,0");
+    // a spaceship phased for long stays one
+    let out = lines(&[
+        "glider", "glider", "--at", "0,0", "--at", "0,20", "--phase", "0", "--phase", "2000", "-n",
+        "0",
+    ]);
+    assert_eq!(out[1], "outcome 1: population 10 in 23×3, at offsets 0,0");
+    let out = lab_error(&["glider", "glider", "--at", "0,0", "--at", "0,70000"]);
+    assert!(out.contains("too far apart"), "{out}");
+    // oriented and run before being placed: the second one flies towards the first
+    let out = lines(&[
+        "glider", "glider", "--at", "0,0", "--at", "0,10", "--orient", "0", "--orient", "m0",
+        "--phase", "0", "--phase", "2", "-n", "8",
+    ]);
+    assert_eq!(out[1], "outcome 1: population 10 in 9×3, at offsets 0,0");
+}
+
+#[test]
+fn lab_arguments() {
+    let lab = |args: &[&str]| {
+        let all = ["cgol-tui", "lab", "glider", "glider"];
+        match Cli::try_parse_from(all.iter().chain(args)) {
+            Ok(Cli {
+                command: Some(Command::Lab(lab)),
+                ..
+            }) => Ok(lab),
+            Ok(cli) => panic!("not lab: {cli:?}"),
+            Err(e) => Err(e.to_string()),
+        }
+    };
+    let args = lab(&[
+        "--at", "-1,2", "--rows", "-3..-1", "--cols", "2..=4", "--orient", "m90",
+    ])
+    .unwrap();
+    assert_eq!(args.at, [(-1, 2)]);
+    assert_eq!((args.rows, args.cols), (-3..=-1, 2..=4));
+    assert_eq!(
+        args.orient,
+        [Orientation {
+            flipped: true,
+            turns: 1
+        }]
+    );
+    let args = lab(&["--rows", "5", "--orient", "270"]).unwrap();
+    assert_eq!(args.rows, 5..=5);
+    assert_eq!(args.cols, 0..=0);
+    assert_eq!(
+        args.orient,
+        [Orientation {
+            flipped: false,
+            turns: 3
+        }]
+    );
+
+    assert!(lab(&["--rows", "3..1"])
+        .unwrap_err()
+        .contains("3 is bigger than 1"));
+    assert!(lab(&["--at", "1"])
+        .unwrap_err()
+        .contains("expected ROW,COL"));
+    assert!(lab(&["--orient", "45"]).is_err());
+    assert!(Cli::try_parse_from(["cgol-tui", "lab", "glider"]).is_err());
+}
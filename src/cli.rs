@@ -0,0 +1,300 @@
+use crate::app::{format::Format, record, Area, Orientation, Renderer, Rule, Theme, Topology};
+use clap::{
+    builder::PossibleValuesParser, builder::TypedValueParser, Args, Parser, Subcommand, ValueEnum,
+};
+use std::{ops::RangeInclusive, path::PathBuf};
+
+/// A Conway's Game of Life viewer TUI.
+#[derive(Parser, Debug)]
+#[command(
+    version,
+    about,
+    args_conflicts_with_subcommands = true,
+    after_help = "patterns in the default library are loaded as well: $CGOL_TUI_LIBRARY if set, \
+                  otherwise $XDG_DATA_HOME/cgol-tui/patterns\n\
+                  settings and keys are read from the config file: --config or $CGOL_TUI_CONFIG if set, \
+                  otherwise $XDG_CONFIG_HOME/cgol-tui/config.toml, command line arguments override it"
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    /// when no subcommand is given: `view`
+    #[command(flatten)]
+    pub view: View,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// View patterns in the TUI, the default
+    View(View),
+    /// Run a pattern for a number of generations without a TUI, then print it
+    Run(Run),
+    /// Convert a pattern to another format, optionally transforming it
+    Convert(Convert),
+    /// Run a pattern until it settles down, then report what it became
+    Analyze(Analyze),
+    /// Measure how fast generations are computed
+    Bench(Bench),
+    /// Collide patterns at a range of offsets, then report the distinct outcomes
+    Lab(Lab),
+    /// Record generations of a pattern as an animated GIF
+    Gif(Gif),
+    /// Print shell completions
+    Completions {
+        #[arg(value_enum)]
+        shell: clap_complete::Shell,
+    },
+}
+
+/// Settings of the simulation
+#[derive(Args, Debug, Clone)]
+pub struct Sim {
+    /// Rule in B/S notation, eg. B36/S23, defaults to the pattern's own
+    #[arg(long)]
+    pub rule: Option<Rule>,
+    /// Size of the universe: <WIDTH>x<HEIGHT>, eg. 200x100,
+    /// without a TUI the board grows with the pattern if not set
+    #[arg(long)]
+    pub size: Option<Area>,
+    /// What's beyond the edges, torus by default in the TUI, an unbounded plane without it
+    #[arg(long, value_parser = topology_parser())]
+    pub topology: Option<Topology>,
+}
+
+#[derive(Args, Debug)]
+pub struct View {
+    /// Pattern files: .cells, .rle or .lif, directories of them, or - for stdin
+    pub patterns: Vec<PathBuf>,
+    #[command(flatten)]
+    pub sim: Sim,
+    /// Config file, see below
+    #[arg(long)]
+    pub config: Option<PathBuf>,
+    /// Initial speed in generations per second, 2.5 by default
+    #[arg(long)]
+    pub speed: Option<f64>,
+    /// How cells are drawn, braille by default, switched with [g]
+    #[arg(long, value_parser = renderer_parser())]
+    pub renderer: Option<Renderer>,
+    /// Colours, dark by default, switched with [c]
+    #[arg(long, value_parser = theme_parser())]
+    pub theme: Option<Theme>,
+    /// Colour cells by age: newly born, long-lived and just died ones differently, toggled with [a]
+    #[arg(long)]
+    pub age: bool,
+    /// Start paused
+    #[arg(long)]
+    pub paused: bool,
+    /// Seed of the random pattern
+    #[arg(long)]
+    pub seed: Option<u64>,
+    /// Index of the pattern to start with
+    #[arg(long)]
+    pub index: Option<usize>,
+    /// Generation to start at, jumping to it as fast as possible
+    #[arg(long = "gen", value_name = "GENERATION")]
+    pub generation: Option<u64>,
+    /// Name of the pattern to start with
+    #[arg(long, conflicts_with = "index")]
+    pub pattern: Option<String>,
+    /// Exit with an error if any of the patterns can't be loaded
+    #[arg(long)]
+    pub strict: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct Run {
+    /// Pattern file, or - for stdin
+    pub pattern: PathBuf,
+    /// Number of generations to run
+    #[arg(short = 'n', long)]
+    pub generations: u64,
+    #[command(flatten)]
+    pub sim: Sim,
+    /// Output format
+    #[arg(short, long, default_value = "cells", value_parser = format_parser())]
+    pub format: Format,
+    /// Print the population of each generation to stderr
+    #[arg(long)]
+    pub population: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct Convert {
+    /// Pattern file, or - for stdin
+    pub input: PathBuf,
+    /// Output file, stdout if not set
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+    /// Input format, guessed from the extension or the content of the input if not set
+    #[arg(short, long, value_parser = format_parser())]
+    pub from: Option<Format>,
+    /// Output format, guessed from the extension of the output file if not set
+    #[arg(short, long, value_parser = format_parser())]
+    pub to: Option<Format>,
+    /// Crop to the live cells, done first
+    #[arg(long)]
+    pub trim: bool,
+    /// Rotate clockwise by degrees, done after trimming
+    #[arg(long, value_parser = rotation_parser())]
+    pub rotate: Option<u8>,
+    /// Mirror, done after rotating, can be given more than once
+    #[arg(long)]
+    pub flip: Vec<Flip>,
+    /// Surround with this many dead cells, done last
+    #[arg(long)]
+    pub pad: Option<u16>,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flip {
+    /// left becomes right
+    Horizontal,
+    /// top becomes bottom
+    Vertical,
+}
+
+#[derive(Args, Debug)]
+pub struct Analyze {
+    /// Pattern file, or - for stdin
+    pub pattern: PathBuf,
+    #[command(flatten)]
+    pub sim: Sim,
+    /// Give up after this many generations
+    #[arg(short = 'n', long, default_value_t = 10_000)]
+    pub max_generations: u64,
+}
+
+#[derive(Args, Debug)]
+pub struct Bench {
+    /// Pattern file, or - for stdin, a random soup if not set
+    pub pattern: Option<PathBuf>,
+    #[command(flatten)]
+    pub sim: Sim,
+    /// Number of generations to run
+    #[arg(short = 'n', long, default_value_t = 1000)]
+    pub generations: u64,
+    /// Seed of the random soup
+    #[arg(long)]
+    pub seed: Option<u64>,
+}
+
+#[derive(Args, Debug)]
+pub struct Gif {
+    /// Pattern file, - for stdin, or the name of a built-in or library pattern
+    pub pattern: String,
+    /// Output file, stdout if not set
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+    #[command(flatten)]
+    pub sim: Sim,
+    /// First generation recorded
+    #[arg(long, default_value_t = 0)]
+    pub from: u64,
+    /// Number of generations recorded
+    #[arg(short = 'n', long, default_value_t = 100)]
+    pub generations: u64,
+    /// Width and height of a cell in pixels
+    #[arg(long, default_value_t = record::CELL_SIZE)]
+    pub cell: u16,
+    /// Colour of live cells: RRGGBB
+    #[arg(long, default_value = "ffffff", value_parser = parse_color)]
+    pub alive: [u8; 3],
+    /// Colour of dead cells: RRGGBB
+    #[arg(long, default_value = "000000", value_parser = parse_color)]
+    pub dead: [u8; 3],
+    /// Time a generation is shown in hundredths of a second
+    #[arg(long, default_value_t = record::DELAY)]
+    pub delay: u16,
+}
+
+#[derive(Args, Debug)]
+pub struct Lab {
+    /// Pattern files, or names of built-in or library patterns, at least two
+    #[arg(num_args = 2.., required = true)]
+    pub patterns: Vec<String>,
+    /// Position of each pattern in order: ROW,COL, eg. 0,10, the ones not given are at 0,0
+    #[arg(long, value_parser = parse_offset, allow_hyphen_values = true)]
+    pub at: Vec<(i32, i32)>,
+    /// Orientation of each pattern in order: 0, 90, 180 or 270° clockwise,
+    /// prefixed with m to mirror first, eg. m90
+    #[arg(long, value_parser = parse_orientation)]
+    pub orient: Vec<Orientation>,
+    /// Generations each pattern in order is run before being placed
+    #[arg(long)]
+    pub phase: Vec<u64>,
+    /// Rows added to the position of the last pattern: FROM..TO, both included
+    #[arg(long, default_value = "0", value_parser = parse_range, allow_hyphen_values = true)]
+    pub rows: RangeInclusive<i32>,
+    /// Columns added to the position of the last pattern: FROM..TO, both included
+    #[arg(long, default_value = "0", value_parser = parse_range, allow_hyphen_values = true)]
+    pub cols: RangeInclusive<i32>,
+    /// Dead cells around the patterns, room for the reaction on a board of --size,
+    /// the board grows beyond them otherwise
+    #[arg(long, default_value_t = 16)]
+    pub margin: u16,
+    #[command(flatten)]
+    pub sim: Sim,
+    /// Number of generations run after placing the patterns
+    #[arg(short = 'n', long, default_value_t = 100)]
+    pub generations: u64,
+    /// Tell outcomes apart by population only, instead of by the cells
+    #[arg(long)]
+    pub census: bool,
+}
+
+fn topology_parser() -> impl TypedValueParser<Value = Topology> {
+    PossibleValuesParser::new(["torus", "plane"]).map(|s| s.parse().unwrap())
+}
+fn renderer_parser() -> impl TypedValueParser<Value = Renderer> {
+    PossibleValuesParser::new(["braille", "halfblock", "block", "ascii"])
+        .map(|s| s.parse().unwrap())
+}
+fn theme_parser() -> impl TypedValueParser<Value = Theme> {
+    PossibleValuesParser::new(Theme::ALL.map(|t| t.name)).map(|s| s.parse().unwrap())
+}
+fn format_parser() -> impl TypedValueParser<Value = Format> {
+    PossibleValuesParser::new(["cells", "rle", "life106", "box"]).map(|s| s.parse().unwrap())
+}
+/// degrees to quarter turns
+fn rotation_parser() -> impl TypedValueParser<Value = u8> {
+    PossibleValuesParser::new(["90", "180", "270"]).map(|s| (s.parse::<u16>().unwrap() / 90) as u8)
+}
+/// ROW,COL
+fn parse_offset(s: &str) -> Result<(i32, i32), String> {
+    let (row, col) = s.split_once(',').ok_or("expected ROW,COL")?;
+    let parse = |n: &str| n.trim().parse::<i32>().map_err(|e| e.to_string());
+    Ok((parse(row)?, parse(col)?))
+}
+fn parse_color(s: &str) -> Result<[u8; 3], String> {
+    record::parse_color(s).map_err(|e| e.to_string())
+}
+/// FROM..TO or a single number
+fn parse_range(s: &str) -> Result<RangeInclusive<i32>, String> {
+    let parse = |n: &str| n.trim().parse::<i32>().map_err(|e| e.to_string());
+    match s.split_once("..") {
+        Some((from, to)) => {
+            let (from, to) = (parse(from)?, parse(to.trim_start_matches('='))?);
+            if from > to {
+                return Err(format!("{from} is bigger than {to}"));
+            }
+            Ok(from..=to)
+        }
+        None => parse(s).map(|n| n..=n),
+    }
+}
+/// degrees, prefixed with m for mirroring first
+fn parse_orientation(s: &str) -> Result<Orientation, String> {
+    let (flipped, degrees) = match s.strip_prefix('m') {
+        Some(degrees) => (true, degrees),
+        None => (false, s),
+    };
+    let turns = match degrees {
+        "" | "0" => 0,
+        "90" => 1,
+        "180" => 2,
+        "270" => 3,
+        _ => return Err("expected 0, 90, 180 or 270, optionally prefixed with m".into()),
+    };
+    Ok(Orientation { flipped, turns })
+}
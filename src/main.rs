@@ -1,50 +1,134 @@
-use app::{App, Universe};
-use std::{io::Read, str::FromStr};
+use app::{
+    config::{self, Config},
+    load, App, Universe,
+};
+use clap::{CommandFactory, Parser};
+use cli::{Cli, Command};
+use crossterm::event::{DisableBracketedPaste, EnableBracketedPaste};
+use std::{io::Read, path::PathBuf};
 
 pub mod app;
+/// Headless modes
+mod batch;
+/// Command line arguments
+mod cli;
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let arg_universes = parse_args()?;
+fn main() {
+    let cli = Cli::parse();
+    let mut out = std::io::stdout().lock();
+    let res = match cli.command.unwrap_or(Command::View(cli.view)) {
+        Command::View(view) => run_tui(view),
+        Command::Run(args) => batch::run(&args, &mut out),
+        Command::Convert(args) => batch::convert(&args, &mut out),
+        Command::Analyze(args) => batch::analyze(&args, &mut out),
+        Command::Bench(args) => batch::bench(&args, &mut out),
+        Command::Lab(args) => batch::lab(&args, &mut out),
+        Command::Gif(args) => batch::gif(&args, &mut out),
+        Command::Completions { shell } => {
+            let name = env!("CARGO_BIN_NAME");
+            clap_complete::generate(shell, &mut Cli::command(), name, &mut out);
+            Ok(())
+        }
+    };
+    if let Err(e) = res {
+        eprintln!("error: {e}");
+        std::process::exit(1);
+    }
+}
 
-    let mut app = App::default().with_universes(arg_universes);
+fn run_tui(view: cli::View) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(seed) = view.seed {
+        fastrand::seed(seed);
+    }
+    let config = match view.config.clone() {
+        Some(path) => Config::load(&path)?,
+        None => match config::default_path().filter(|path| path.exists()) {
+            Some(path) => Config::load(&path)?,
+            None => Config::default(),
+        },
+    };
+    let (arg_universes, failed) = load_patterns(&view, &config.library)?;
+
+    let app = App::default()
+        .with_universes(arg_universes)
+        .with_keymap(config.keymap)
+        .with_speed(view.speed.or(config.speed).unwrap_or(app::DEF_SPEED))
+        .with_renderer(view.renderer.or(config.renderer).unwrap_or_default())
+        .with_theme(view.theme.or(config.theme).unwrap_or_default())
+        .with_aging(view.age)
+        .with_paused(view.paused)
+        .with_rule(view.sim.rule.or(config.rule))
+        .with_topology(view.sim.topology.or(config.topology).unwrap_or_default())
+        .with_size(view.sim.size)
+        .with_generation(view.generation);
+    let mut app = match (view.index, view.pattern.or(config.pattern)) {
+        (Some(i), _) => app.with_index(i)?,
+        (None, Some(name)) => app.with_pattern(&name)?,
+        (None, None) => app,
+    };
+    // shown in the TUI as well, stderr is hidden behind it until quitting
+    app.message = failed;
 
     let mut terminal = ratatui::try_init()?;
+    crossterm::execute!(std::io::stdout(), EnableBracketedPaste)?;
 
     let res = app.run(&mut terminal);
 
+    crossterm::execute!(std::io::stdout(), DisableBracketedPaste)?;
     ratatui::try_restore()?;
 
     Ok(res?)
 }
 
-fn parse_args() -> Result<Vec<Universe>, Box<dyn std::error::Error>> {
-    let args = std::env::args().skip(1).collect::<Vec<_>>();
-    if args.contains(&"-h".into()) || args.contains(&"--help".into()) {
-        println!(
-            "A Conway's Game of Life viewer TUI.
-            
-USAGE: cgol-tui [<pattern>,...]
-
-where <pattern> is either a .cells file, or - for stdin"
-        );
-        std::process::exit(0);
-    }
-    let piped_universe = {
+/// Patterns given as arguments, then those in `library` and the default library,
+/// with a summary of the ones that failed to load, if any
+fn load_patterns(
+    view: &cli::View,
+    library: &[PathBuf],
+) -> Result<(Vec<Universe>, Option<String>), Box<dyn std::error::Error>> {
+    let piped_universe = if view.patterns.iter().any(|p| p.as_os_str() == "-") {
         let mut univ = String::new();
-        if args.len() == 1 && args[0] == "-" {
-            std::io::stdin().read_to_string(&mut univ)?;
-        }
-        if univ.is_empty() {
-            vec![]
-        } else {
-            vec![Universe::from_str(&univ)?]
-        }
+        std::io::stdin().read_to_string(&mut univ)?;
+        vec![load::parse("-".as_ref(), &univ)]
+    } else {
+        vec![]
     };
-    let universes = args
+    let default_library = load::default_library().filter(|path| path.is_dir());
+    let universes = view
+        .patterns
         .iter()
-        .flat_map(std::fs::read_to_string)
-        .flat_map(|s| Universe::from_str(&s))
+        .filter(|p| p.as_os_str() != "-")
+        .cloned()
+        .chain(library.iter().cloned())
+        .chain(default_library)
+        .flat_map(|path| load::load(&path));
+
+    let (universes, errors): (Vec<_>, Vec<_>) =
+        universes.chain(piped_universe).partition(Result::is_ok);
+    let universes = universes.into_iter().flatten().collect::<Vec<_>>();
+    let errors = errors
+        .into_iter()
+        .filter_map(Result::err)
         .collect::<Vec<_>>();
 
-    Ok([universes, piped_universe].concat())
+    let Some(first) = errors.first() else {
+        return Ok((universes, None));
+    };
+    let summary = format!(
+        "loaded {} patterns, failed to load {}",
+        universes.len(),
+        errors.len()
+    );
+    eprintln!("{summary}:");
+    for e in &errors {
+        eprintln!("    {e}");
+    }
+    if view.strict {
+        std::process::exit(1);
+    }
+    let more = match errors.len() {
+        1 => String::new(),
+        n => format!(" and {} more", n - 1),
+    };
+    Ok((universes, Some(format!("{summary}: {first}{more}"))))
 }